@@ -10,16 +10,24 @@ contract DeploySettlement is Script {
         uint256 deployerPrivateKey = vm.envUint("PRIVATE_KEY");
         bytes32 vkey = vm.envBytes32("VKEY");
         address sp1Verifier = vm.envAddress("SP1_VERIFIER");
+        string memory network = vm.envOr("BANKAI_NETWORK", string("sepolia"));
+        uint8 bankaiNetwork;
+        if (keccak256(bytes(network)) == keccak256("mainnet")) {
+            bankaiNetwork = 1;
+        } else {
+            require(keccak256(bytes(network)) == keccak256("sepolia"), "BANKAI_NETWORK must be sepolia or mainnet");
+        }
         
         vm.startBroadcast(deployerPrivateKey);
         
         // Deploy with empty order roots array
         bytes32[] memory roots = new bytes32[](0);
-        SettlementContract settlement = new SettlementContract(roots, vkey, sp1Verifier);
+        SettlementContract settlement = new SettlementContract(roots, vkey, sp1Verifier, bankaiNetwork);
         
         console.log("SettlementContract deployed to:", address(settlement));
         console.log("VKey:", uint256(vkey));
         console.log("SP1 Verifier:", sp1Verifier);
+        console.log("Bankai network:", network);
         
         vm.stopBroadcast();
     }
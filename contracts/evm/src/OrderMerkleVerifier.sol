@@ -10,35 +10,107 @@ contract SettlementContract {
         uint64 sourceChainId;
         uint64 destinationChainId;
         address receiver;
+        address token; // address(0) for native ETH
         uint256 amount;
         uint64 blockNumber;
+        uint64 fillDeadline; // last block the fill may be included in
     }
 
+    /// @notice Public values committed by the SP1 settlement program (`settlement_lib::SettlementOutput`)
+    struct SettlementOutput {
+        uint8 version;
+        uint8 treeMode;
+        uint8 bankaiNetwork;
+        bytes32 ordersRoot;
+        bytes32 nullifiersRoot;
+        uint64 blockNumber;
+        bytes32 blockHash;
+        uint64 destinationChainId;
+        uint64 orderCount;
+        bytes32 accumulatorsRoot; // tree over the batch's AccumulatorTransitions, one per settlement chain
+    }
+
+    /// @notice How a batch moves one settlement chain's order accumulator (`settlement_lib::AccumulatorTransition`)
+    struct AccumulatorTransition {
+        uint64 chainId;
+        bytes32 previousRoot; // accumulator of the chain's orders proven before this batch
+        bytes32 root; // accumulator including this batch's orders from the chain
+        uint64 count;
+    }
+
+    /// @notice This chain's accumulator transition and its proof against `accumulatorsRoot`
+    struct AccumulatorProof {
+        AccumulatorTransition transition;
+        bytes32[] proof;
+    }
+
+    uint8 public constant SETTLEMENT_OUTPUT_VERSION = 6;
+    /// @notice Raw hashes as leaves (`settlement_lib::MerkleTreeMode::Legacy`)
+    uint8 public constant TREE_MODE_LEGACY = 0;
+    /// @notice OpenZeppelin StandardMerkleTree double-hashed leaves (`MerkleTreeMode::Standard`)
+    uint8 public constant TREE_MODE_STANDARD = 1;
+    /// @notice Bankai network ids (`settlement_lib::BankaiNetwork`)
+    uint8 public constant BANKAI_NETWORK_SEPOLIA = 0;
+    uint8 public constant BANKAI_NETWORK_MAINNET = 1;
+    /// @notice Root of the order accumulator before any order was proven (`settlement_lib::EMPTY_ACCUMULATOR_ROOT`)
+    bytes32 public constant EMPTY_ACCUMULATOR_ROOT = 0x27ae5ba08d7291c96c8cbddcc148bf48a6d68c7974b94356f53754ef6171d757;
+
     struct OrderProof {
         bytes32 orderHash;
         bytes32[] proof;
         uint256 leafIndex;
+        bytes32 nullifier;
+        bytes32[] nullifierProof;
+    }
+
+    /// @notice Multiproof over the orders tree, leaves in the order `multiProofVerify` consumes them
+    struct OrderMultiProof {
+        bytes32[] orderHashes;
+        bytes32[] proof;
+        bool[] proofFlags;
+    }
+
+    /// @notice Multiproof over the nullifiers tree for the same orders
+    /// @dev orderIndices[i] is the position in OrderMultiProof.orderHashes that nullifiers[i] belongs to
+    struct NullifierMultiProof {
+        uint256[] orderIndices;
+        bytes32[] nullifiers;
+        bytes32[] proof;
+        bool[] proofFlags;
     }
 
     event OrderSettled(bytes32 orderHash);
     event NewOrder(Order order);
 
     mapping(bytes32 => bool) public orderMapping;
+    /// @notice Order hash that consumed each fill nullifier
+    /// @dev Local to this contract: the program only accepts a fill whose calldata names its
+    /// order, so a fill can't belong to an order settling on another chain
+    mapping(bytes32 => bytes32) public nullifierMapping;
     bytes32 public vk;
     address public verifier;
+    /// @notice The only Bankai network whose proofs this contract accepts
+    uint8 public immutable bankaiNetwork;
+    /// @notice Root of this chain's order accumulator after the last settled batch; the next
+    /// batch with orders from this chain must extend it
+    bytes32 public accumulatorRoot;
 
-    constructor(bytes32[] memory roots, bytes32 _vk, address _verifier) {
+    constructor(bytes32[] memory roots, bytes32 _vk, address _verifier, uint8 _bankaiNetwork) {
+        require(_bankaiNetwork <= BANKAI_NETWORK_MAINNET, "Unsupported Bankai network");
         for (uint256 i = 0; i < roots.length; i++) {
             orderMapping[roots[i]] = false;
         }
         vk = _vk;
         verifier = _verifier;
+        bankaiNetwork = _bankaiNetwork;
+        accumulatorRoot = EMPTY_ACCUMULATOR_ROOT;
     }
 
     function submitOrder(
         Order memory order
     ) public {
         require(order.sourceChainId == block.chainid, "Wrong chain id set");
+        require(order.fillDeadline >= order.blockNumber, "Invalid fill window");
         bytes32 orderHash = hashOrder(order);
         require(!orderMapping[orderHash], "Order already exists");
         orderMapping[orderHash] = false;
@@ -48,33 +120,155 @@ contract SettlementContract {
     function settleOrders(
         bytes calldata publicValues,
         bytes calldata proofBytes,
+        AccumulatorProof memory accumulator,
         OrderProof[] memory orderProofs
     ) public {
 
         // verify the zk proof
         ISP1Verifier(verifier).verifyProof(vk, publicValues, proofBytes);
         
-        // Extract merkle root from bytes 0..32 of publicValues
-        bytes32 merkleRoot;
-        assembly {
-            merkleRoot := calldataload(publicValues.offset)
-        }
-        
+        SettlementOutput memory output = decodeSettlementOutput(publicValues);
+        advanceAccumulator(output, accumulator);
+        bytes32 merkleRoot = output.ordersRoot;
+
         for (uint256 i = 0; i < orderProofs.length; i++) {
             OrderProof memory orderProof = orderProofs[i];
-            bool valid = MerkleProof.verify(orderProof.proof, merkleRoot, orderProof.orderHash);
+            bytes32 leaf = treeLeaf(orderProof.orderHash, output.treeMode);
+            bool valid = MerkleProof.verify(orderProof.proof, merkleRoot, leaf);
             require(valid, "Invalid merkle proof");
+            consumeNullifier(orderProof, output.nullifiersRoot, output.treeMode);
             orderMapping[orderProof.orderHash] = true;
             emit OrderSettled(orderProof.orderHash);
         }
     }
 
+    /// @notice Settles many orders with one multiproof per tree instead of a path per order
+    /// @dev Only supported for outputs committed with the standard tree layout
+    function settleOrdersMultiproof(
+        bytes calldata publicValues,
+        bytes calldata proofBytes,
+        AccumulatorProof memory accumulator,
+        OrderMultiProof memory orders,
+        NullifierMultiProof memory nullifiers
+    ) public {
+        ISP1Verifier(verifier).verifyProof(vk, publicValues, proofBytes);
+
+        SettlementOutput memory output = decodeSettlementOutput(publicValues);
+        require(output.treeMode == TREE_MODE_STANDARD, "Multiproofs need the standard tree");
+        advanceAccumulator(output, accumulator);
+
+        uint256 count = orders.orderHashes.length;
+        require(
+            nullifiers.orderIndices.length == count && nullifiers.nullifiers.length == count,
+            "Nullifier count mismatch"
+        );
+
+        bytes32[] memory leaves = new bytes32[](count);
+        for (uint256 i = 0; i < count; i++) {
+            leaves[i] = treeLeaf(orders.orderHashes[i], output.treeMode);
+        }
+        bool valid = MerkleProof.multiProofVerify(orders.proof, orders.proofFlags, output.ordersRoot, leaves);
+        require(valid, "Invalid merkle proof");
+
+        // every order must be paired with exactly one nullifier
+        bool[] memory paired = new bool[](count);
+        for (uint256 i = 0; i < count; i++) {
+            uint256 orderIndex = nullifiers.orderIndices[i];
+            require(orderIndex < count && !paired[orderIndex], "Invalid nullifier pairing");
+            paired[orderIndex] = true;
+
+            bytes32 orderHash = orders.orderHashes[orderIndex];
+            bytes32 nullifier = nullifiers.nullifiers[i];
+            leaves[i] = treeLeaf(keccak256(abi.encode(orderHash, nullifier)), output.treeMode);
+
+            bytes32 consumedBy = nullifierMapping[nullifier];
+            require(consumedBy == bytes32(0) || consumedBy == orderHash, "Fill already used by another order");
+            nullifierMapping[nullifier] = orderHash;
+        }
+        valid = MerkleProof.multiProofVerify(nullifiers.proof, nullifiers.proofFlags, output.nullifiersRoot, leaves);
+        require(valid, "Invalid nullifier proof");
+
+        for (uint256 i = 0; i < count; i++) {
+            orderMapping[orders.orderHashes[i]] = true;
+            emit OrderSettled(orders.orderHashes[i]);
+        }
+    }
+
     function resetOrders(bytes32[] memory orderHashes) public {
         for (uint256 i = 0; i < orderHashes.length; i++) {
             orderMapping[orderHashes[i]] = false;
         }
     }
 
+    /// @notice Moves the stored accumulator root to this chain's root after the proven batch
+    /// @dev The transition must be this chain's and committed in the output. It must extend the
+    /// stored root, so the batches holding this chain's orders settle in the order they were
+    /// proven; batches without orders from this chain don't move it. A batch whose root is
+    /// already stored may settle again, e.g. split over several transactions.
+    /// @param output The decoded settlement output
+    /// @param accumulator This chain's transition and its proof against `output.accumulatorsRoot`
+    function advanceAccumulator(SettlementOutput memory output, AccumulatorProof memory accumulator) internal {
+        AccumulatorTransition memory transition = accumulator.transition;
+        require(transition.chainId == block.chainid, "Wrong settlement chain");
+        bytes32 leaf = treeLeaf(keccak256(abi.encode(transition)), output.treeMode);
+        require(
+            MerkleProof.verify(accumulator.proof, output.accumulatorsRoot, leaf), "Invalid accumulator proof"
+        );
+
+        if (transition.root == accumulatorRoot) {
+            return;
+        }
+        require(transition.previousRoot == accumulatorRoot, "Accumulator root mismatch");
+        accumulatorRoot = transition.root;
+    }
+
+    /// @notice Records the fill that settled an order, rejecting fills already used by another order
+    /// @param orderProof The order proof carrying the nullifier and its merkle proof
+    /// @param nullifiersRoot The nullifier root committed by the SP1 program
+    /// @param treeMode The tree layout committed by the SP1 program
+    function consumeNullifier(
+        OrderProof memory orderProof,
+        bytes32 nullifiersRoot,
+        uint8 treeMode
+    ) internal {
+        bytes32 leaf = treeLeaf(
+            keccak256(abi.encode(orderProof.orderHash, orderProof.nullifier)),
+            treeMode
+        );
+        bool valid = MerkleProof.verify(orderProof.nullifierProof, nullifiersRoot, leaf);
+        require(valid, "Invalid nullifier proof");
+
+        bytes32 consumedBy = nullifierMapping[orderProof.nullifier];
+        require(
+            consumedBy == bytes32(0) || consumedBy == orderProof.orderHash,
+            "Fill already used by another order"
+        );
+        nullifierMapping[orderProof.nullifier] = orderProof.orderHash;
+    }
+
+    /// @notice Decodes the public values committed by the SP1 program
+    /// @param publicValues The ABI-encoded SettlementOutput
+    /// @return output The decoded settlement output
+    function decodeSettlementOutput(
+        bytes calldata publicValues
+    ) public view returns (SettlementOutput memory output) {
+        output = abi.decode(publicValues, (SettlementOutput));
+        require(output.version == SETTLEMENT_OUTPUT_VERSION, "Unsupported output version");
+        require(output.treeMode <= TREE_MODE_STANDARD, "Unsupported tree mode");
+        require(output.bankaiNetwork == bankaiNetwork, "Wrong Bankai network");
+    }
+
+    /// @notice Computes the merkle leaf for a hash under the committed tree layout
+    /// @param hash The order hash or nullifier leaf hash
+    /// @param treeMode The tree layout committed by the SP1 program
+    /// @return The leaf as stored in the tree
+    function treeLeaf(bytes32 hash, uint8 treeMode) public pure returns (bytes32) {
+        if (treeMode == TREE_MODE_STANDARD) {
+            return keccak256(bytes.concat(hash));
+        }
+        return hash;
+    }
+
     /// @notice Hashes an order using keccak256
     /// @param order The order to hash
     /// @return The hash of the order
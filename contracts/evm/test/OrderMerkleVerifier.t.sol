@@ -5,6 +5,11 @@ import "forge-std/Test.sol";
 import "../src/OrderMerkleVerifier.sol";
 import "@openzeppelin/contracts/utils/cryptography/MerkleProof.sol";
 
+/// @notice Accepts every proof, so settlement can be tested without a real SP1 proof
+contract AcceptingVerifier {
+    function verifyProof(bytes32, bytes calldata, bytes calldata) external pure {}
+}
+
 contract OrderMerkleVerifierTest is Test {
     SettlementContract public verifier;
 
@@ -12,7 +17,7 @@ contract OrderMerkleVerifierTest is Test {
         bytes32[] memory roots = new bytes32[](0);
         bytes32 vk = bytes32(0);
         address mockVerifier = address(0);
-        verifier = new SettlementContract(roots, vk, mockVerifier);
+        verifier = new SettlementContract(roots, vk, mockVerifier, 0);
     }
     
     function verifyOrder(
@@ -35,8 +40,10 @@ contract OrderMerkleVerifierTest is Test {
                 sourceChainId: uint64(vm.parseJsonUint(json, ".proofs[0].order.source_chain_id")),
                 destinationChainId: uint64(vm.parseJsonUint(json, ".proofs[0].order.destination_chain_id")),
                 receiver: vm.parseJsonAddress(json, ".proofs[0].order.receiver"),
+                token: vm.parseJsonAddress(json, ".proofs[0].order.token"),
                 amount: vm.parseJsonUint(json, ".proofs[0].order.amount"),
-                blockNumber: uint64(vm.parseJsonUint(json, ".proofs[0].order.block_number"))
+                blockNumber: uint64(vm.parseJsonUint(json, ".proofs[0].order.block_number")),
+                fillDeadline: uint64(vm.parseJsonUint(json, ".proofs[0].order.fill_deadline"))
             });
             
             bytes32[] memory proof = new bytes32[](1);
@@ -51,8 +58,10 @@ contract OrderMerkleVerifierTest is Test {
                 sourceChainId: uint64(vm.parseJsonUint(json, ".proofs[1].order.source_chain_id")),
                 destinationChainId: uint64(vm.parseJsonUint(json, ".proofs[1].order.destination_chain_id")),
                 receiver: vm.parseJsonAddress(json, ".proofs[1].order.receiver"),
+                token: vm.parseJsonAddress(json, ".proofs[1].order.token"),
                 amount: vm.parseJsonUint(json, ".proofs[1].order.amount"),
-                blockNumber: uint64(vm.parseJsonUint(json, ".proofs[1].order.block_number"))
+                blockNumber: uint64(vm.parseJsonUint(json, ".proofs[1].order.block_number")),
+                fillDeadline: uint64(vm.parseJsonUint(json, ".proofs[1].order.fill_deadline"))
             });
             
             bytes32[] memory proof = new bytes32[](1);
@@ -74,16 +83,20 @@ contract OrderMerkleVerifierTest is Test {
             sourceChainId: 2,
             destinationChainId: 1,
             receiver: 0x797b212C0a4cB61DEC7dC491B632b72D854e03fd,
+            token: address(0),
             amount: 273418440000000000,
-            blockNumber: 9451455
+            blockNumber: 9451455,
+            fillDeadline: 9451455
         });
 
         SettlementContract.Order memory order2 = SettlementContract.Order({
             sourceChainId: 1,
             destinationChainId: 2,
             receiver: 0x1234567890123456789012345678901234567890,
+            token: address(0),
             amount: 100000000000000000,
-            blockNumber: 1000000
+            blockNumber: 1000000,
+            fillDeadline: 1000000
         });
 
         // For a tree with 2 leaves, each leaf's proof contains the other leaf
@@ -124,8 +137,10 @@ contract OrderMerkleVerifierTest is Test {
             sourceChainId: 2,
             destinationChainId: 1,
             receiver: 0x797b212C0a4cB61DEC7dC491B632b72D854e03fd,
+            token: address(0),
             amount: 273418440000000000,
-            blockNumber: 9451455
+            blockNumber: 9451455,
+            fillDeadline: 9451455
         });
 
         bytes32[] memory invalidProof = new bytes32[](1);
@@ -153,8 +168,10 @@ contract OrderMerkleVerifierTest is Test {
                 sourceChainId: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.source_chain_id"))),
                 destinationChainId: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.destination_chain_id"))),
                 receiver: vm.parseJsonAddress(json, string.concat(basePath, ".order.receiver")),
+                token: vm.parseJsonAddress(json, string.concat(basePath, ".order.token")),
                 amount: vm.parseJsonUint(json, string.concat(basePath, ".order.amount")),
-                blockNumber: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.block_number")))
+                blockNumber: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.block_number"))),
+                fillDeadline: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.fill_deadline")))
             });
             
             // Parse proof array
@@ -185,8 +202,10 @@ contract OrderMerkleVerifierTest is Test {
                 sourceChainId: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.source_chain_id"))),
                 destinationChainId: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.destination_chain_id"))),
                 receiver: vm.parseJsonAddress(json, string.concat(basePath, ".order.receiver")),
+                token: vm.parseJsonAddress(json, string.concat(basePath, ".order.token")),
                 amount: vm.parseJsonUint(json, string.concat(basePath, ".order.amount")),
-                blockNumber: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.block_number")))
+                blockNumber: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.block_number"))),
+                fillDeadline: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.fill_deadline")))
             });
             
             // Parse proof array (3 elements for a tree with 5 leaves)
@@ -201,5 +220,301 @@ contract OrderMerkleVerifierTest is Test {
         
         console.log("All 5 orders verified successfully!");
     }
-}
 
+    /// @notice Test with 5 orders in the OpenZeppelin StandardMerkleTree layout (double-hashed leaves)
+    function test_VerifyProofs_Standard5Orders() public {
+        string memory json = vm.readFile("./src/fixtures/merkle_proofs_standard_5.json");
+        bytes32 root = vm.parseJsonBytes32(json, ".root");
+
+        for (uint i = 0; i < 5; i++) {
+            string memory basePath = string.concat(".proofs[", vm.toString(i), "]");
+
+            SettlementContract.Order memory order = SettlementContract.Order({
+                sourceChainId: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.source_chain_id"))),
+                destinationChainId: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.destination_chain_id"))),
+                receiver: vm.parseJsonAddress(json, string.concat(basePath, ".order.receiver")),
+                token: vm.parseJsonAddress(json, string.concat(basePath, ".order.token")),
+                amount: vm.parseJsonUint(json, string.concat(basePath, ".order.amount")),
+                blockNumber: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.block_number"))),
+                fillDeadline: uint64(vm.parseJsonUint(json, string.concat(basePath, ".order.fill_deadline")))
+            });
+
+            // Proof depth varies per leaf in the standard layout
+            bytes32[] memory proof = vm.parseJsonBytes32Array(json, string.concat(basePath, ".proof"));
+
+            bytes32 leaf = verifier.treeLeaf(verifier.hashOrder(order), verifier.TREE_MODE_STANDARD());
+            assertTrue(MerkleProof.verify(proof, root, leaf), string.concat("Order ", vm.toString(i), " should be valid"));
+
+            // The raw order hash must not verify as a leaf
+            assertFalse(MerkleProof.verify(proof, root, verifier.hashOrder(order)));
+        }
+    }
+
+    /// @notice Test a Rust-generated multiproof for 3 of the 5 standard-layout orders
+    function test_VerifyMultiProof_Standard5Orders() public {
+        string memory json = vm.readFile("./src/fixtures/merkle_multiproof_standard_5.json");
+        bytes32 root = vm.parseJsonBytes32(json, ".root");
+        bytes32[] memory orderHashes = vm.parseJsonBytes32Array(json, ".orderHashes");
+        bytes32[] memory proof = vm.parseJsonBytes32Array(json, ".proof");
+        bool[] memory proofFlags = vm.parseJsonBoolArray(json, ".proofFlags");
+
+        bytes32[] memory leaves = new bytes32[](orderHashes.length);
+        for (uint i = 0; i < orderHashes.length; i++) {
+            leaves[i] = verifier.treeLeaf(orderHashes[i], verifier.TREE_MODE_STANDARD());
+        }
+        assertTrue(MerkleProof.multiProofVerify(proof, proofFlags, root, leaves), "Multiproof should be valid");
+
+        // Leaves must be supplied in the order the proof consumes them
+        (leaves[0], leaves[1]) = (leaves[1], leaves[0]);
+        assertFalse(MerkleProof.multiProofVerify(proof, proofFlags, root, leaves), "Reordered leaves should be rejected");
+    }
+
+    function settlementOutput(bytes32 ordersRoot, bytes32 nullifiersRoot, bytes32 accumulatorsRoot)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        return abi.encode(
+            SettlementContract.SettlementOutput({
+                version: 6,
+                treeMode: 1,
+                bankaiNetwork: 0,
+                ordersRoot: ordersRoot,
+                nullifiersRoot: nullifiersRoot,
+                blockNumber: 0,
+                blockHash: bytes32(0),
+                destinationChainId: 11155111,
+                orderCount: 0,
+                accumulatorsRoot: accumulatorsRoot
+            })
+        );
+    }
+
+    function transition(uint256 chainId, bytes32 previousRoot, bytes32 root, uint64 count)
+        internal
+        pure
+        returns (SettlementContract.AccumulatorTransition memory)
+    {
+        return SettlementContract.AccumulatorTransition({
+            chainId: uint64(chainId),
+            previousRoot: previousRoot,
+            root: root,
+            count: count
+        });
+    }
+
+    /// @notice Standard-layout leaf of an accumulator transition
+    function accumulatorLeaf(SettlementContract.AccumulatorTransition memory t) internal pure returns (bytes32) {
+        return keccak256(bytes.concat(keccak256(abi.encode(t))));
+    }
+
+    /// @notice Public values committing a batch with a single accumulator transition
+    function singleTransition(SettlementContract.AccumulatorTransition memory t)
+        internal
+        pure
+        returns (bytes memory publicValues, SettlementContract.AccumulatorProof memory accumulator)
+    {
+        accumulator.transition = t;
+        accumulator.proof = new bytes32[](0);
+        publicValues = settlementOutput(bytes32(0), bytes32(0), accumulatorLeaf(t));
+    }
+
+    /// @notice Batches with orders from this chain must extend its stored accumulator root, in the order they were proven
+    function test_AccumulatorRootAdvancesInOrder() public {
+        bytes32[] memory roots = new bytes32[](0);
+        SettlementContract settlement =
+            new SettlementContract(roots, bytes32(0), address(new AcceptingVerifier()), 0);
+        SettlementContract.OrderProof[] memory none = new SettlementContract.OrderProof[](0);
+        bytes32 empty = settlement.EMPTY_ACCUMULATOR_ROOT();
+        assertEq(settlement.accumulatorRoot(), empty);
+
+        bytes32 first = keccak256("first batch");
+        bytes32 second = keccak256("second batch");
+        (bytes memory firstBatch, SettlementContract.AccumulatorProof memory firstAccumulator) =
+            singleTransition(transition(block.chainid, empty, first, 2));
+        (bytes memory secondBatch, SettlementContract.AccumulatorProof memory secondAccumulator) =
+            singleTransition(transition(block.chainid, first, second, 4));
+
+        // a batch that doesn't start from the stored root is rejected
+        vm.expectRevert("Accumulator root mismatch");
+        settlement.settleOrders(secondBatch, "", secondAccumulator, none);
+
+        settlement.settleOrders(firstBatch, "", firstAccumulator, none);
+        assertEq(settlement.accumulatorRoot(), first);
+
+        // the same batch may settle again, e.g. split over several transactions
+        settlement.settleOrders(firstBatch, "", firstAccumulator, none);
+        assertEq(settlement.accumulatorRoot(), first);
+
+        settlement.settleOrders(secondBatch, "", secondAccumulator, none);
+        assertEq(settlement.accumulatorRoot(), second);
+
+        // an older batch can't roll the root back
+        vm.expectRevert("Accumulator root mismatch");
+        settlement.settleOrders(firstBatch, "", firstAccumulator, none);
+    }
+
+    /// @notice Settles batch N, moving this chain's accumulator to `root` and another chain's
+    /// alongside it; returns the other chain's new root
+    function settleWithOtherChain(SettlementContract settlement, bytes32 root, uint256 otherChain)
+        internal
+        returns (bytes32 otherRoot)
+    {
+        bytes32 empty = settlement.EMPTY_ACCUMULATOR_ROOT();
+        otherRoot = keccak256("other chain, batch N");
+        SettlementContract.AccumulatorProof memory accumulator;
+        accumulator.transition = transition(block.chainid, empty, root, 2);
+        accumulator.proof = new bytes32[](1);
+        accumulator.proof[0] = accumulatorLeaf(transition(otherChain, empty, otherRoot, 1));
+
+        bytes memory publicValues = settlementOutput(
+            bytes32(0), bytes32(0), hashPair(accumulatorLeaf(accumulator.transition), accumulator.proof[0])
+        );
+        settlement.settleOrders(publicValues, "", accumulator, new SettlementContract.OrderProof[](0));
+    }
+
+    /// @notice A batch without orders from this chain doesn't move its root, so the next batch
+    /// with orders from this chain still settles
+    function test_SettlesBatchAfterSkippingOne() public {
+        bytes32[] memory roots = new bytes32[](0);
+        SettlementContract settlement =
+            new SettlementContract(roots, bytes32(0), address(new AcceptingVerifier()), 0);
+        SettlementContract.OrderProof[] memory none = new SettlementContract.OrderProof[](0);
+        uint256 otherChain = block.chainid + 1;
+
+        // batch N: orders from this chain and another one
+        bytes32 first = keccak256("this chain, batch N");
+        bytes32 otherRoot = settleWithOtherChain(settlement, first, otherChain);
+        assertEq(settlement.accumulatorRoot(), first);
+
+        // batch N+1 only holds orders from the other chain, whose transition can't settle here
+        (bytes memory skipped, SettlementContract.AccumulatorProof memory otherAccumulator) =
+            singleTransition(transition(otherChain, otherRoot, keccak256("other chain, batch N+1"), 2));
+        vm.expectRevert("Wrong settlement chain");
+        settlement.settleOrders(skipped, "", otherAccumulator, none);
+
+        // batch N+2 extends this chain's root from batch N
+        bytes32 second = keccak256("this chain, batch N+2");
+        (bytes memory nextBatch, SettlementContract.AccumulatorProof memory accumulator) =
+            singleTransition(transition(block.chainid, first, second, 3));
+
+        // a transition the batch doesn't commit is rejected
+        SettlementContract.AccumulatorProof memory forged;
+        forged.transition = transition(block.chainid, first, keccak256("forged"), 3);
+        forged.proof = accumulator.proof;
+        vm.expectRevert("Invalid accumulator proof");
+        settlement.settleOrders(nextBatch, "", forged, none);
+
+        settlement.settleOrders(nextBatch, "", accumulator, none);
+        assertEq(settlement.accumulatorRoot(), second);
+    }
+
+    function hashPair(bytes32 a, bytes32 b) internal pure returns (bytes32) {
+        return uint256(a) < uint256(b) ? keccak256(abi.encodePacked(a, b)) : keccak256(abi.encodePacked(b, a));
+    }
+
+    /// @notice Two standard-layout orders proven against the same fill, as two batches would commit them
+    struct FillReplay {
+        SettlementContract settlement;
+        bytes publicValues;
+        SettlementContract.AccumulatorProof accumulator;
+        bytes32 fill;
+        bytes32 first;
+        bytes32 second;
+        bytes32 firstLeaf;
+        bytes32 secondLeaf;
+        bytes32 firstNullifierLeaf;
+        bytes32 secondNullifierLeaf;
+    }
+
+    function fillReplay() internal returns (FillReplay memory r) {
+        bytes32[] memory roots = new bytes32[](0);
+        r.settlement = new SettlementContract(roots, bytes32(0), address(new AcceptingVerifier()), 0);
+        uint8 standard = r.settlement.TREE_MODE_STANDARD();
+
+        r.fill = keccak256("fill tx");
+        r.first = keccak256("first order");
+        r.second = keccak256("second order");
+        r.firstLeaf = r.settlement.treeLeaf(r.first, standard);
+        r.secondLeaf = r.settlement.treeLeaf(r.second, standard);
+        r.firstNullifierLeaf = r.settlement.treeLeaf(keccak256(abi.encode(r.first, r.fill)), standard);
+        r.secondNullifierLeaf = r.settlement.treeLeaf(keccak256(abi.encode(r.second, r.fill)), standard);
+
+        r.accumulator.transition =
+            transition(block.chainid, r.settlement.EMPTY_ACCUMULATOR_ROOT(), keccak256("batch"), 2);
+        r.accumulator.proof = new bytes32[](0);
+        r.publicValues = settlementOutput(
+            hashPair(r.firstLeaf, r.secondLeaf),
+            hashPair(r.firstNullifierLeaf, r.secondNullifierLeaf),
+            accumulatorLeaf(r.accumulator.transition)
+        );
+    }
+
+    function singleProof(bytes32 orderHash, bytes32 sibling, bytes32 fill, bytes32 nullifierSibling)
+        internal
+        pure
+        returns (SettlementContract.OrderProof[] memory proofs)
+    {
+        proofs = new SettlementContract.OrderProof[](1);
+        proofs[0].orderHash = orderHash;
+        proofs[0].proof = new bytes32[](1);
+        proofs[0].proof[0] = sibling;
+        proofs[0].nullifier = fill;
+        proofs[0].nullifierProof = new bytes32[](1);
+        proofs[0].nullifierProof[0] = nullifierSibling;
+    }
+
+    function singleMultiproof(bytes32 orderHash, bytes32 sibling, bytes32 fill, bytes32 nullifierSibling)
+        internal
+        pure
+        returns (SettlementContract.OrderMultiProof memory orders, SettlementContract.NullifierMultiProof memory nullifiers)
+    {
+        orders.orderHashes = new bytes32[](1);
+        orders.orderHashes[0] = orderHash;
+        orders.proof = new bytes32[](1);
+        orders.proof[0] = sibling;
+        orders.proofFlags = new bool[](1);
+
+        nullifiers.orderIndices = new uint256[](1);
+        nullifiers.nullifiers = new bytes32[](1);
+        nullifiers.nullifiers[0] = fill;
+        nullifiers.proof = new bytes32[](1);
+        nullifiers.proof[0] = nullifierSibling;
+        nullifiers.proofFlags = new bool[](1);
+    }
+
+    /// @notice A fill settled through settleOrders can't settle another order through settleOrdersMultiproof
+    function test_RejectFillReusedAcrossMultiproof() public {
+        FillReplay memory r = fillReplay();
+
+        r.settlement.settleOrders(
+            r.publicValues, "", r.accumulator, singleProof(r.first, r.secondLeaf, r.fill, r.secondNullifierLeaf)
+        );
+        assertEq(r.settlement.nullifierMapping(r.fill), r.first);
+
+        (SettlementContract.OrderMultiProof memory orders, SettlementContract.NullifierMultiProof memory nullifiers) =
+            singleMultiproof(r.second, r.firstLeaf, r.fill, r.firstNullifierLeaf);
+        vm.expectRevert("Fill already used by another order");
+        r.settlement.settleOrdersMultiproof(r.publicValues, "", r.accumulator, orders, nullifiers);
+
+        // the order that consumed the fill may still settle again
+        r.settlement.settleOrders(
+            r.publicValues, "", r.accumulator, singleProof(r.first, r.secondLeaf, r.fill, r.secondNullifierLeaf)
+        );
+    }
+
+    /// @notice A fill settled through settleOrdersMultiproof can't settle another order through settleOrders
+    function test_RejectFillReusedAcrossSingleProofs() public {
+        FillReplay memory r = fillReplay();
+
+        (SettlementContract.OrderMultiProof memory orders, SettlementContract.NullifierMultiProof memory nullifiers) =
+            singleMultiproof(r.first, r.secondLeaf, r.fill, r.secondNullifierLeaf);
+        r.settlement.settleOrdersMultiproof(r.publicValues, "", r.accumulator, orders, nullifiers);
+        assertEq(r.settlement.nullifierMapping(r.fill), r.first);
+
+        SettlementContract.OrderProof[] memory replay =
+            singleProof(r.second, r.firstLeaf, r.fill, r.firstNullifierLeaf);
+        vm.expectRevert("Fill already used by another order");
+        r.settlement.settleOrders(r.publicValues, "", r.accumulator, replay);
+    }
+}
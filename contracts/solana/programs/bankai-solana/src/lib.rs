@@ -3,11 +3,16 @@ use anchor_lang::prelude::*;
 pub mod merkle;
 pub mod state;
 
-use merkle::verify_merkle_proof_keccak;
+use alloy_primitives::U256;
+use merkle::{tree_leaf_keccak, verify_merkle_proof_keccak};
+use settlement_lib::{BankaiNetwork, MerkleTreeMode, SettlementOutput, BANKAI_VKEY_HASH};
 use state::*;
 
 declare_id!("HpgNxwdekXixEW6ZzTPsjhhFx46fpfoC7ruJvsinPYHx");
-const BANKAI_VKEY_HASH: &str = "0x009c661e44c7e5e76f0aafdfab8ceb7c76357cc5ba5863a7dfa0b306807f8c02";
+/// The only Bankai network whose proofs this program accepts
+const BANKAI_NETWORK: BankaiNetwork = BankaiNetwork::Sepolia;
+/// Chain id this program settles orders for, matching `chain_id` in `script/chains.toml`
+const SETTLEMENT_CHAIN_ID: u64 = 103;
 #[program]
 pub mod bankai_solana {
     use super::*;
@@ -16,6 +21,40 @@ pub mod bankai_solana {
         let state = &mut ctx.accounts.state;
         state.vkey_hash = vkey_hash;
         state.bump = ctx.bumps.state;
+        state.accumulator_root = settlement_lib::EMPTY_ACCUMULATOR_ROOT.0;
+        Ok(())
+    }
+
+    /// Grows a state account created before the accumulator root was stored and starts it
+    /// from the empty accumulator. The vkey hash and bump are kept.
+    pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+        let state = &ctx.accounts.state;
+        require!(
+            state.data_len() < SettlementState::SPACE,
+            SettlementError::StateAlreadyMigrated
+        );
+
+        let rent = Rent::get()?;
+        let top_up = rent
+            .minimum_balance(SettlementState::SPACE)
+            .saturating_sub(state.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: state.to_account_info(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        state.resize(SettlementState::SPACE)?;
+
+        let mut data = state.try_borrow_mut_data()?;
+        data[SettlementState::SPACE - 32..]
+            .copy_from_slice(&settlement_lib::EMPTY_ACCUMULATOR_ROOT.0);
         Ok(())
     }
 
@@ -25,8 +64,12 @@ pub mod bankai_solana {
         order_hash: [u8; 32],
     ) -> Result<()> {
         // Sanity check: recompute hash and ensure it matches provided order_hash
-        let computed = order_hash_keccak(&order);
+        let computed = order.hash();
         require!(computed == order_hash, SettlementError::InvalidOrderHash);
+        require!(
+            order.fill_deadline >= order.block_number,
+            SettlementError::InvalidFillWindow
+        );
         let order_status = &mut ctx.accounts.order_status;
         order_status.order_hash = computed;
         order_status.settled = false;
@@ -37,17 +80,20 @@ pub mod bankai_solana {
             source_chain_id: order.source_chain_id,
             destination_chain_id: order.destination_chain_id,
             receiver: order.receiver,
+            token: order.token,
             amount: order.amount,
             block_number: order.block_number,
+            fill_deadline: order.fill_deadline,
         });
 
         Ok(())
     }
 
-    pub fn settle_orders(
-        ctx: Context<SettleOrders>,
+    pub fn settle_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleOrders<'info>>,
         sp1_public_inputs: Vec<u8>,
         groth16_proof: Vec<u8>,
+        accumulator: AccumulatorProof,
         order_proofs: Vec<OrderProof>,
     ) -> Result<()> {
         let state = &mut ctx.accounts.state;
@@ -57,28 +103,72 @@ pub mod bankai_solana {
         sp1_solana::verify_proof(&groth16_proof, &sp1_public_inputs, BANKAI_VKEY_HASH, vk)
             .map_err(|_| error!(SettlementError::InvalidProof))?;
 
-        // Extract merkle root from bytes 0..32
+        // Decode the committed SettlementOutput
+        let output = SettlementOutput::decode(&sp1_public_inputs)
+            .map_err(|_| error!(SettlementError::InvalidPublicInputs))?;
+        let tree_mode = MerkleTreeMode::try_from(output.tree_mode)
+            .map_err(|_| error!(SettlementError::InvalidPublicInputs))?;
         require!(
-            sp1_public_inputs.len() >= 32,
-            SettlementError::InvalidPublicInputs
+            output.bankai_network == BANKAI_NETWORK as u8,
+            SettlementError::WrongBankaiNetwork
         );
-        let merkle_root: [u8; 32] = sp1_public_inputs[0..32]
-            .try_into()
-            .map_err(|_| error!(SettlementError::InvalidPublicInputs))?;
+
+        // The proof commits one accumulator transition per settlement chain; only ours moves
+        // the stored root
+        let transition = &accumulator.transition;
+        require!(
+            transition.chain_id == SETTLEMENT_CHAIN_ID,
+            SettlementError::WrongSettlementChain
+        );
+        let leaf = tree_leaf_keccak(&transition.hash(), tree_mode);
+        let ok = verify_merkle_proof_keccak(&leaf, &accumulator.proof, &output.accumulators_root.0);
+        require!(ok, SettlementError::InvalidAccumulatorProof);
+
+        // Batches carrying orders for this chain settle in the order they were proven. A batch
+        // whose root is already stored may settle again, as the client sends one order per
+        // transaction.
+        if transition.root != state.accumulator_root {
+            require!(
+                transition.previous_root == state.accumulator_root,
+                SettlementError::AccumulatorRootMismatch
+            );
+            state.accumulator_root = transition.root;
+        }
+        let merkle_root = output.orders_root.0;
 
         for (i, op) in order_proofs.iter().enumerate() {
             // Recompute order hash from full order
-            let h = order_hash_keccak(&op.order);
+            let h = op.order.hash();
             require!(h == op.order_hash, SettlementError::InvalidOrderHash);
 
-            let ok = verify_merkle_proof_keccak(&h, &op.proof, &merkle_root);
+            let leaf = tree_leaf_keccak(&h, tree_mode);
+            let ok = verify_merkle_proof_keccak(&leaf, &op.proof, &merkle_root);
             require!(ok, SettlementError::InvalidMerkleProof);
 
-            // Use remaining accounts to access the PDA for this order
+            // Bind the order to the fill it was proven with
+            let leaf = tree_leaf_keccak(&nullifier_leaf_keccak(&h, &op.nullifier), tree_mode);
+            let ok =
+                verify_merkle_proof_keccak(&leaf, &op.nullifier_proof, &output.nullifiers_root.0);
+            require!(ok, SettlementError::InvalidNullifierProof);
+
+            // Use remaining accounts to access the order and nullifier PDAs for this order
             let acct_info = ctx
                 .remaining_accounts
-                .get(i)
+                .get(2 * i)
                 .ok_or(error!(SettlementError::InvalidPublicInputs))?;
+            let nullifier_info = ctx
+                .remaining_accounts
+                .get(2 * i + 1)
+                .ok_or(error!(SettlementError::InvalidPublicInputs))?;
+
+            consume_nullifier(
+                ctx.program_id,
+                nullifier_info,
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                &op.nullifier,
+                &h,
+            )?;
 
             // Ensure PDA address matches seeds
             let (expected_pda, _bump) =
@@ -140,8 +230,64 @@ pub struct Order {
     pub source_chain_id: u64,
     pub destination_chain_id: u64,
     pub receiver: [u8; 20],
+    pub token: [u8; 20],
     pub amount: [u8; 32],
     pub block_number: u64,
+    pub fill_deadline: u64,
+}
+
+impl Order {
+    /// keccak256(abi.encode(order)), computed by `settlement_lib` like the prover does
+    pub fn hash(&self) -> [u8; 32] {
+        settlement_lib::Order::from(self).hash().0
+    }
+}
+
+impl From<&Order> for settlement_lib::Order {
+    fn from(order: &Order) -> Self {
+        Self {
+            source_chain_id: order.source_chain_id,
+            destination_chain_id: order.destination_chain_id,
+            receiver: order.receiver.into(),
+            token: order.token.into(),
+            amount: U256::from_be_bytes(order.amount),
+            block_number: order.block_number,
+            fill_deadline: order.fill_deadline,
+        }
+    }
+}
+
+/// This chain's accumulator transition and its path to the committed `accumulators_root`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AccumulatorProof {
+    pub transition: AccumulatorTransition,
+    pub proof: Vec<[u8; 32]>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AccumulatorTransition {
+    pub chain_id: u64,
+    pub previous_root: [u8; 32],
+    pub root: [u8; 32],
+    pub count: u64,
+}
+
+impl AccumulatorTransition {
+    /// keccak256(abi.encode(transition)), computed by `settlement_lib` like the prover does
+    pub fn hash(&self) -> [u8; 32] {
+        settlement_lib::AccumulatorTransition::from(self).hash().0
+    }
+}
+
+impl From<&AccumulatorTransition> for settlement_lib::AccumulatorTransition {
+    fn from(transition: &AccumulatorTransition) -> Self {
+        Self {
+            chain_id: transition.chain_id,
+            previous_root: transition.previous_root.into(),
+            root: transition.root.into(),
+            count: transition.count,
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
@@ -149,21 +295,64 @@ pub struct OrderProof {
     pub order: Order,
     pub order_hash: [u8; 32],
     pub proof: Vec<[u8; 32]>,
+    pub nullifier: [u8; 32],
+    pub nullifier_proof: Vec<[u8; 32]>,
 }
 
-fn order_hash_keccak(order: &Order) -> [u8; 32] {
-    // keccak256(abi.encode(order)) with Solidity static encoding (32-byte words)
-    use solana_program::keccak::hashv;
+fn nullifier_leaf_keccak(order_hash: &[u8; 32], nullifier: &[u8; 32]) -> [u8; 32] {
+    // keccak256(abi.encode(orderHash, nullifier))
+    settlement_lib::nullifier_leaf((*order_hash).into(), (*nullifier).into()).0
+}
 
-    let mut w1 = [0u8; 32];
-    w1[24..].copy_from_slice(&order.source_chain_id.to_be_bytes());
-    let mut w2 = [0u8; 32];
-    w2[24..].copy_from_slice(&order.destination_chain_id.to_be_bytes());
-    let mut w3 = [0u8; 32];
-    w3[12..].copy_from_slice(&order.receiver);
-    let mut w5 = [0u8; 32];
-    w5[24..].copy_from_slice(&order.block_number.to_be_bytes());
-    hashv(&[&w1, &w2, &w3, &order.amount, &w5]).to_bytes()
+/// Records the order that consumed a fill nullifier, creating its PDA on first use.
+/// Re-settling the same order is allowed, settling a different order with the same fill is not.
+fn consume_nullifier<'info>(
+    program_id: &Pubkey,
+    acct_info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    nullifier: &[u8; 32],
+    order_hash: &[u8; 32],
+) -> Result<()> {
+    let (expected_pda, bump) =
+        Pubkey::find_program_address(&[b"nullifier", nullifier.as_ref()], program_id);
+    require_keys_eq!(expected_pda, *acct_info.key);
+
+    if !acct_info.data_is_empty() {
+        let data: state::NullifierStatus =
+            state::NullifierStatus::try_deserialize(&mut &acct_info.data.borrow()[..])?;
+        require!(
+            data.order_hash == *order_hash,
+            SettlementError::NullifierAlreadyUsed
+        );
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::CreateAccount {
+                from: payer.to_account_info(),
+                to: acct_info.clone(),
+            },
+            &[&[b"nullifier", nullifier.as_ref(), &[bump]]],
+        ),
+        rent.minimum_balance(NullifierStatus::SPACE),
+        NullifierStatus::SPACE as u64,
+        program_id,
+    )?;
+
+    let data = state::NullifierStatus {
+        nullifier: *nullifier,
+        order_hash: *order_hash,
+        bump,
+    };
+    let mut data_buf = acct_info.data.borrow_mut();
+    let mut cursor = std::io::Cursor::new(&mut data_buf[..]);
+    data.try_serialize(&mut cursor)?;
+
+    Ok(())
 }
 
 fn hex_string(bytes32: [u8; 32]) -> String {
@@ -185,6 +374,22 @@ pub enum SettlementError {
     InvalidMerkleProof,
     #[msg("Order hash mismatch")]
     InvalidOrderHash,
+    #[msg("Invalid nullifier proof")]
+    InvalidNullifierProof,
+    #[msg("Fill already used by another order")]
+    NullifierAlreadyUsed,
+    #[msg("Proof is for a different Bankai network")]
+    WrongBankaiNetwork,
+    #[msg("Fill deadline is before the order block")]
+    InvalidFillWindow,
+    #[msg("Proof does not extend the stored accumulator root")]
+    AccumulatorRootMismatch,
+    #[msg("Accumulator transition is for a different settlement chain")]
+    WrongSettlementChain,
+    #[msg("Invalid accumulator proof")]
+    InvalidAccumulatorProof,
+    #[msg("State account is already migrated")]
+    StateAlreadyMigrated,
 }
 
 #[event]
@@ -193,8 +398,10 @@ pub struct OrderSubmitted {
     pub source_chain_id: u64,
     pub destination_chain_id: u64,
     pub receiver: [u8; 20],
+    pub token: [u8; 20],
     pub amount: [u8; 32],
     pub block_number: u64,
+    pub fill_deadline: u64,
 }
 
 #[event]
@@ -212,6 +419,17 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    /// CHECK: a state account in the old layout doesn't deserialize as `SettlementState`; the
+    /// seeds pin the address and the owner check pins the program
+    #[account(mut, seeds = [b"state"], bump, owner = crate::ID)]
+    pub state: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 #[instruction(order: Order, order_hash: [u8; 32])]
 pub struct SubmitOrder<'info> {
@@ -234,6 +452,9 @@ pub struct SubmitOrder<'info> {
 pub struct SettleOrders<'info> {
     #[account(mut, seeds = [b"state"], bump = state.bump)]
     pub state: Account<'info, SettlementState>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -243,3 +464,100 @@ pub struct ResetOrders<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_hash_matches_settlement_lib() {
+        let order = Order {
+            source_chain_id: 84532,
+            destination_chain_id: 11155111,
+            receiver: [0x11; 20],
+            token: [0x22; 20],
+            amount: U256::from(1_000_000_u64).to_be_bytes(),
+            block_number: 9_000_000,
+            fill_deadline: 9_000_100,
+        };
+        let expected = settlement_lib::Order {
+            source_chain_id: 84532,
+            destination_chain_id: 11155111,
+            receiver: [0x11; 20].into(),
+            token: [0x22; 20].into(),
+            amount: U256::from(1_000_000_u64),
+            block_number: 9_000_000,
+            fill_deadline: 9_000_100,
+        }
+        .hash();
+
+        assert_eq!(order.hash(), expected.0);
+    }
+
+    #[test]
+    fn accumulator_transition_hash_matches_settlement_lib() {
+        let transition = AccumulatorTransition {
+            chain_id: SETTLEMENT_CHAIN_ID,
+            previous_root: settlement_lib::EMPTY_ACCUMULATOR_ROOT.0,
+            root: [0x33; 32],
+            count: 2,
+        };
+        let expected = settlement_lib::AccumulatorTransition {
+            chain_id: SETTLEMENT_CHAIN_ID,
+            previous_root: settlement_lib::EMPTY_ACCUMULATOR_ROOT,
+            root: [0x33; 32].into(),
+            count: 2,
+        }
+        .hash();
+
+        assert_eq!(transition.hash(), expected.0);
+    }
+
+    #[test]
+    fn errors_match_settlement_lib() {
+        use SettlementError::*;
+
+        let errors = [
+            InvalidProof,
+            InvalidPublicInputs,
+            InvalidMerkleProof,
+            InvalidOrderHash,
+            InvalidNullifierProof,
+            NullifierAlreadyUsed,
+            WrongBankaiNetwork,
+            InvalidFillWindow,
+            AccumulatorRootMismatch,
+            WrongSettlementChain,
+            InvalidAccumulatorProof,
+            StateAlreadyMigrated,
+        ];
+        // Exhaustive, so a new variant doesn't compile until it's added to `errors` above
+        for error in errors {
+            match error {
+                InvalidProof
+                | InvalidPublicInputs
+                | InvalidMerkleProof
+                | InvalidOrderHash
+                | InvalidNullifierProof
+                | NullifierAlreadyUsed
+                | WrongBankaiNetwork
+                | InvalidFillWindow
+                | AccumulatorRootMismatch
+                | WrongSettlementChain
+                | InvalidAccumulatorProof
+                | StateAlreadyMigrated => {}
+            }
+        }
+
+        let actual: Vec<(u32, String, String)> = errors
+            .into_iter()
+            .map(|error| (u32::from(error), error.name(), error.to_string()))
+            .collect();
+        let expected: Vec<(u32, String, String)> = settlement_lib::SOLANA_PROGRAM_ERRORS
+            .iter()
+            .zip(anchor_lang::error::ERROR_CODE_OFFSET..)
+            .map(|((name, message), code)| (code, name.to_string(), message.to_string()))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,14 +1,12 @@
-use solana_program::keccak::hashv;
+use settlement_lib::{process_proof, MerkleTreeMode};
 
-fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
-    let (left, right) = if a <= b { (a, b) } else { (b, a) };
-    hashv(&[left, right]).to_bytes()
+/// Computes the tree leaf for a hash under the committed tree layout.
+pub fn tree_leaf_keccak(hash: &[u8; 32], tree_mode: MerkleTreeMode) -> [u8; 32] {
+    tree_mode.leaf((*hash).into()).0
 }
 
+/// Verifies a sorted-pair keccak path with `settlement_lib`, the same code that built it.
 pub fn verify_merkle_proof_keccak(leaf: &[u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
-    let mut computed = *leaf;
-    for p in proof.iter() {
-        computed = hash_pair(&computed, p);
-    }
-    &computed == root
+    let proof: Vec<_> = proof.iter().map(|sibling| (*sibling).into()).collect();
+    process_proof((*leaf).into(), &proof).0 == *root
 }
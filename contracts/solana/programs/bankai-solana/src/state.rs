@@ -4,12 +4,15 @@ use anchor_lang::prelude::*;
 pub struct SettlementState {
     pub vkey_hash: [u8; 32],
     pub bump: u8,
+    /// Accumulator root after the last settled batch; the next proof must extend it
+    pub accumulator_root: [u8; 32],
 }
 
 impl SettlementState {
     pub const SPACE: usize = 8  // discriminator
         + 32 // vkey_hash
-        + 1; // bump
+        + 1  // bump
+        + 32; // accumulator_root
 }
 
 #[account]
@@ -25,3 +28,17 @@ impl OrderStatus {
         + 1  // settled
         + 1; // bump
 }
+
+#[account]
+pub struct NullifierStatus {
+    pub nullifier: [u8; 32],
+    pub order_hash: [u8; 32],
+    pub bump: u8,
+}
+
+impl NullifierStatus {
+    pub const SPACE: usize = 8  // discriminator
+        + 32 // nullifier
+        + 32 // order_hash
+        + 1; // bump
+}
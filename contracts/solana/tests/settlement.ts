@@ -38,13 +38,19 @@ describe("bankai-salana settlement", () => {
     // transform order into program types
     const amountHex = BigInt(op.order.amount).toString(16).padStart(64, "0");
     const receiverBuf = Buffer.from(op.order.receiver.replace(/^0x/, ""), "hex");
+    const tokenBuf = Buffer.from(
+      (op.order.token ?? "0x0000000000000000000000000000000000000000").replace(/^0x/, ""),
+      "hex"
+    );
     const amountBuf = Buffer.from(amountHex, "hex");
     const order = {
       sourceChainId: new BN(op.order.source_chain_id),
       destinationChainId: new BN(op.order.destination_chain_id),
       receiver: [...receiverBuf],
+      token: [...tokenBuf],
       amount: [...amountBuf],
       blockNumber: new BN(op.order.block_number),
+      fillDeadline: new BN(op.order.fill_deadline),
     };
 
     const orderHash = Buffer.from(op.order_hash.replace(/^0x/, ""), "hex");
@@ -68,6 +74,26 @@ describe("bankai-salana settlement", () => {
     const merkleProof: number[][] = op.proof.map((h: string) =>
       [...Buffer.from(h.replace(/^0x/, ""), "hex")]
     );
+    const nullifier = Buffer.from(op.nullifier.replace(/^0x/, ""), "hex");
+    const nullifierProof: number[][] = op.nullifier_proof.map((h: string) =>
+      [...Buffer.from(h.replace(/^0x/, ""), "hex")]
+    );
+    // this chain's accumulator transition, proven against the committed accumulators root
+    const acc = proof.accumulators[firstKey];
+    const bytes32 = (h: string) => [...Buffer.from(h.replace(/^0x/, ""), "hex")];
+    const accumulator = {
+      transition: {
+        chainId: new BN(acc.transition.chain_id),
+        previousRoot: bytes32(acc.transition.previous_root),
+        root: bytes32(acc.transition.root),
+        count: new BN(acc.transition.count),
+      },
+      proof: acc.proof.map(bytes32),
+    };
+    const [nullifierPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nullifier"), nullifier],
+      program.programId
+    );
 
     // 🧩 Add compute budget increase before settleOrders
     const modifyComputeUnits = ComputeBudgetProgram.setComputeUnitLimit({
@@ -87,11 +113,25 @@ describe("bankai-salana settlement", () => {
           .settleOrders(
             pv,
             pf,
-            [{ order: order, orderHash: [...orderHash], proof: merkleProof }]
+            accumulator,
+            [
+              {
+                order: order,
+                orderHash: [...orderHash],
+                proof: merkleProof,
+                nullifier: [...nullifier],
+                nullifierProof: nullifierProof,
+              },
+            ]
           )
-          .accounts({ state: statePda })
+          .accounts({
+            state: statePda,
+            payer: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
           .remainingAccounts([
             { pubkey: orderPda, isWritable: true, isSigner: false },
+            { pubkey: nullifierPda, isWritable: true, isSigner: false },
           ])
           .instruction()
       );
@@ -4,13 +4,25 @@ set -e
 NETWORK="${1:-sepolia}"
 ACCOUNT="${2:-}"
 VK="${3:-0x009c661e44c7e5e76f0aafdfab8ceb7c76357cc5ba5863a7dfa0b306807f8c02}"
+BANKAI_NETWORK="${4:-sepolia}"
+# Must match chain_id for this chain in script/chains.toml
+CHAIN_ID="${5:-393402133025997798}"
 
 if [ -z "$ACCOUNT" ]; then
     echo "Error: Account name required"
-    echo "Usage: ./deploy.sh <network> <account-name> [verification-key]"
+    echo "Usage: ./deploy.sh <network> <account-name> [verification-key] [bankai-network] [chain-id]"
     exit 1
 fi
 
+case "$BANKAI_NETWORK" in
+    sepolia) BANKAI_NETWORK_ID=0 ;;
+    mainnet) BANKAI_NETWORK_ID=1 ;;
+    *)
+        echo "Error: Bankai network must be sepolia or mainnet"
+        exit 1
+        ;;
+esac
+
 echo "Building contract..."
 scarb build
 
@@ -38,7 +50,9 @@ VK_PADDED=$(printf "%064s" "$VK_STRIPPED" | tr ' ' '0')
 VK_HIGH=$(echo "$VK_PADDED" | cut -c1-32)
 VK_LOW=$(echo "$VK_PADDED" | cut -c33-64)
 echo "VK (u256): high=0x$VK_HIGH, low=0x$VK_LOW"
-DEPLOY_OUTPUT=$(sncast --profile "$NETWORK" --account "$ACCOUNT" deploy --class-hash "$CLASS_HASH" --constructor-calldata 0 0x$VK_LOW 0x$VK_HIGH 2>&1)
+echo "Bankai network: $BANKAI_NETWORK ($BANKAI_NETWORK_ID)"
+echo "Settlement chain id: $CHAIN_ID"
+DEPLOY_OUTPUT=$(sncast --profile "$NETWORK" --account "$ACCOUNT" deploy --class-hash "$CLASS_HASH" --constructor-calldata 0 0x$VK_LOW 0x$VK_HIGH $BANKAI_NETWORK_ID $CHAIN_ID 2>&1)
 CONTRACT_ADDRESS=$(echo "$DEPLOY_OUTPUT" | grep -o 'contract_address: 0x[0-9a-fA-F]*' | sed 's/contract_address: //')
 
 if [ -z "$CONTRACT_ADDRESS" ]; then
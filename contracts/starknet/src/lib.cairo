@@ -6,12 +6,14 @@ pub trait ISettlementContract<TContractState> {
     fn settle_orders(
         ref self: TContractState,
         proof: Array<felt252>,
+        accumulator: AccumulatorProof,
         order_proofs: Span<OrderProof>
     );
     fn reset_orders(ref self: TContractState, order_hashes: Span<u256>);
     fn hash_order(self: @TContractState, order: Order) -> u256;
     fn get_order_status(self: @TContractState, order_hash: u256) -> bool;
     fn get_vk(self: @TContractState) -> u256;
+    fn get_accumulator_root(self: @TContractState) -> u256;
     // Test/utility views
     fn verify_merkle_proof_public(
         self: @TContractState,
@@ -28,8 +30,42 @@ pub struct Order {
     pub source_chain_id: u64,
     pub destination_chain_id: u64,
     pub receiver: u256,
+    pub token: u256, // 0 for native ETH
     pub amount: u256,
     pub block_number: u64,
+    pub fill_deadline: u64, // last block the fill may be included in
+}
+
+/// Public values committed by the SP1 program (`settlement_lib::SettlementOutput`),
+/// one u256 per ABI word.
+#[derive(Copy, Drop, Serde)]
+pub struct SettlementOutput {
+    pub version: u8,
+    pub tree_mode: u8,
+    pub bankai_network: u8,
+    pub orders_root: u256,
+    pub nullifiers_root: u256,
+    pub block_number: u64,
+    pub block_hash: u256,
+    pub destination_chain_id: u64,
+    pub order_count: u64,
+    pub accumulators_root: u256,
+}
+
+/// One settlement chain's accumulator step (`settlement_lib::AccumulatorTransition`).
+#[derive(Copy, Drop, Serde)]
+pub struct AccumulatorTransition {
+    pub chain_id: u64,
+    pub previous_root: u256,
+    pub root: u256,
+    pub count: u64,
+}
+
+/// This chain's accumulator transition and its path to the committed `accumulators_root`.
+#[derive(Copy, Drop, Serde)]
+pub struct AccumulatorProof {
+    pub transition: AccumulatorTransition,
+    pub proof: Span<u256>,
 }
 
 #[derive(Copy, Drop, Serde)]
@@ -37,11 +73,13 @@ pub struct OrderProof {
     pub order_hash: u256,
     pub proof: Span<u256>,
     pub leaf_index: u256,
+    pub nullifier: u256,
+    pub nullifier_proof: Span<u256>,
 }
 
 #[starknet::contract]
 pub mod SettlementContract {
-    use super::{Order, OrderProof};
+    use super::{AccumulatorProof, Order, OrderProof, SettlementOutput};
     use starknet::{SyscallResultTrait, get_tx_info};
     use starknet::syscalls::library_call_syscall;
     use core::keccak::keccak_u256s_be_inputs;
@@ -55,10 +93,29 @@ pub mod SettlementContract {
     const SP1_VERIFIER_CLASS_HASH: felt252 =
         0x79b72f62c1c6aad55c0ee0ecc68132a32db268306a19c451c35191080b7b611;
 
+    /// Layout version of the committed SettlementOutput.
+    const SETTLEMENT_OUTPUT_VERSION: u8 = 6;
+    /// `settlement_lib::MerkleTreeMode::Standard`: OpenZeppelin StandardMerkleTree double-hashed leaves.
+    const TREE_MODE_STANDARD: u8 = 1;
+    /// `settlement_lib::BankaiNetwork::Mainnet`, the highest supported Bankai network id.
+    const BANKAI_NETWORK_MAINNET: u8 = 1;
+    /// `settlement_lib::EMPTY_ACCUMULATOR_ROOT`, the order accumulator before any order was proven.
+    const EMPTY_ACCUMULATOR_ROOT: u256 =
+        0x27ae5ba08d7291c96c8cbddcc148bf48a6d68c7974b94356f53754ef6171d757;
+
     #[storage]
     struct Storage {
         order_mapping: Map<u256, bool>,
+        // nullifier -> order hash that consumed the fill
+        nullifier_mapping: Map<u256, u256>,
         vk: u256,
+        // the only Bankai network whose proofs are accepted
+        bankai_network: u8,
+        // chain id this contract settles orders for, as in script/chains.toml
+        chain_id: u64,
+        // accumulator root after the last batch with orders for this chain; the next proof
+        // carrying orders for it must extend it
+        accumulator_root: u256,
     }
 
     #[event]
@@ -82,8 +139,11 @@ pub mod SettlementContract {
     fn constructor(
         ref self: ContractState,
         roots: Span<u256>,
-        vk: u256
+        vk: u256,
+        bankai_network: u8,
+        chain_id: u64
     ) {
+        assert(bankai_network <= BANKAI_NETWORK_MAINNET, 'Unsupported Bankai network');
         let mut i: u32 = 0;
         loop {
             if i >= roots.len() {
@@ -93,6 +153,9 @@ pub mod SettlementContract {
             i += 1;
         };
         self.vk.write(vk);
+        self.bankai_network.write(bankai_network);
+        self.chain_id.write(chain_id);
+        self.accumulator_root.write(EMPTY_ACCUMULATOR_ROOT);
     }
 
     #[abi(embed_v0)]
@@ -102,7 +165,8 @@ pub mod SettlementContract {
             // let tx_info = get_tx_info().unbox();
             // let current_chain_id: u64 = tx_info.chain_id.try_into().unwrap();
             // assert(order.source_chain_id == current_chain_id, 'Wrong chain id set');
-            
+            assert(order.fill_deadline >= order.block_number, 'Invalid fill window');
+
             let order_hash = self.hash_order(order);
             assert(!self.order_mapping.entry(order_hash).read(), 'Order already exists');
             
@@ -113,6 +177,7 @@ pub mod SettlementContract {
         fn settle_orders(
             ref self: ContractState,
             proof: Array<felt252>,
+            accumulator: AccumulatorProof,
             order_proofs: Span<OrderProof>
         ) {
             // Step 1: Call the Garaga SP1 Verifier to validate the proof cryptographically
@@ -136,11 +201,32 @@ pub mod SettlementContract {
             // Step 5: Verify this proof corresponds to our expected SP1 program
             assert(vk == self.vk.read(), 'Wrong program');
 
-            // Step 6: Extract merkle root from public inputs
-            // The public inputs contain the merkle root from the SP1 program
-            let merkle_root = self._extract_merkle_root(public_inputs);
+            // Step 6: Decode the settlement output committed by the SP1 program
+            let output = self._decode_settlement_output(public_inputs);
+
+            // Step 7: The proof commits one accumulator transition per settlement chain; check
+            // ours is among them
+            let transition = accumulator.transition;
+            assert(transition.chain_id == self.chain_id.read(), 'Wrong settlement chain');
+            let valid = self._verify_merkle_proof(
+                accumulator.proof,
+                output.accumulators_root,
+                self._tree_leaf(self._hash_transition(accumulator), output.tree_mode),
+                0
+            );
+            assert(valid, 'Invalid accumulator proof');
 
-            // Step 7: Verify and settle each order
+            // Step 8: Batches carrying orders for this chain settle in the order they were
+            // proven. A batch whose root is already stored may settle again, e.g. split over
+            // several transactions.
+            let stored_root = self.accumulator_root.read();
+            if transition.root != stored_root {
+                assert(transition.previous_root == stored_root, 'Accumulator root mismatch');
+                self.accumulator_root.write(transition.root);
+            }
+            let merkle_root = output.orders_root;
+
+            // Step 9: Verify and settle each order
             let mut i: u32 = 0;
             loop {
                 if i >= order_proofs.len() {
@@ -151,11 +237,13 @@ pub mod SettlementContract {
                 let valid = self._verify_merkle_proof(
                     order_proof.proof,
                     merkle_root,
-                    order_proof.order_hash,
+                    self._tree_leaf(order_proof.order_hash, output.tree_mode),
                     order_proof.leaf_index
                 );
                 
                 assert(valid, 'Invalid merkle proof');
+
+                self._consume_nullifier(order_proof, output.nullifiers_root, output.tree_mode);
                 
                 self.order_mapping.entry(order_proof.order_hash).write(true);
                 self.emit(OrderSettled { order_hash: order_proof.order_hash });
@@ -185,9 +273,11 @@ pub mod SettlementContract {
             
             // Receiver already provided as EVM-style u256 address
             data.append(order.receiver);
+            data.append(order.token);
             
             data.append(order.amount);
             data.append(order.block_number.into());
+            data.append(order.fill_deadline.into());
 
             // Use keccak (big-endian inputs), then reverse bytes to match Solidity's big-endian output
             let hashed = keccak_u256s_be_inputs(data.span());
@@ -206,6 +296,10 @@ pub mod SettlementContract {
             self.vk.read()
         }
 
+        fn get_accumulator_root(self: @ContractState) -> u256 {
+            self.accumulator_root.read()
+        }
+
         fn verify_merkle_proof_public(
             self: @ContractState,
             proof: Span<u256>,
@@ -229,17 +323,106 @@ pub mod SettlementContract {
 
     #[generate_trait]
     impl InternalFunctions of InternalFunctionsTrait {
-        fn _extract_merkle_root(
+        fn _decode_settlement_output(
             self: @ContractState,
             public_inputs: Span<u256>
-        ) -> u256 {
-            // Extract merkle root from public inputs
-            // The SP1 program commits the merkle root as the first 32 bytes of public values
+        ) -> SettlementOutput {
+            // The SP1 program commits abi.encode(SettlementOutput): ten static 32-byte words
             // The public inputs are parsed as u256 values by the verifier
-            assert(public_inputs.len() >= 1, 'Invalid public inputs length');
-            
-            // Return the first public input as the merkle root (bytes 0..32)
-            *public_inputs.at(0)
+            assert(public_inputs.len() == 10, 'Invalid public inputs length');
+
+            let version: u8 = (*public_inputs.at(0)).try_into().expect('Invalid output version');
+            assert(version == SETTLEMENT_OUTPUT_VERSION, 'Unsupported output version');
+
+            let tree_mode: u8 = (*public_inputs.at(1)).try_into().expect('Invalid tree mode');
+            assert(tree_mode <= TREE_MODE_STANDARD, 'Unsupported tree mode');
+
+            let bankai_network: u8 = (*public_inputs.at(2))
+                .try_into()
+                .expect('Invalid Bankai network');
+            assert(bankai_network == self.bankai_network.read(), 'Wrong Bankai network');
+
+            SettlementOutput {
+                version,
+                tree_mode,
+                bankai_network,
+                orders_root: *public_inputs.at(3),
+                nullifiers_root: *public_inputs.at(4),
+                block_number: (*public_inputs.at(5)).try_into().expect('Invalid block number'),
+                block_hash: *public_inputs.at(6),
+                destination_chain_id: (*public_inputs.at(7))
+                    .try_into()
+                    .expect('Invalid destination chain'),
+                order_count: (*public_inputs.at(8)).try_into().expect('Invalid order count'),
+                accumulators_root: *public_inputs.at(9),
+            }
+        }
+
+        fn _hash_transition(self: @ContractState, accumulator: AccumulatorProof) -> u256 {
+            // keccak256(abi.encode(transition)), matching settlement_lib::AccumulatorTransition
+            let transition = accumulator.transition;
+            let mut data: Array<u256> = ArrayTrait::new();
+            data.append(transition.chain_id.into());
+            data.append(transition.previous_root);
+            data.append(transition.root);
+            data.append(transition.count.into());
+            let h = keccak_u256s_be_inputs(data.span());
+            u256 {
+                low: integer::u128_byte_reverse(h.high),
+                high: integer::u128_byte_reverse(h.low)
+            }
+        }
+
+        fn _tree_leaf(self: @ContractState, hash: u256, tree_mode: u8) -> u256 {
+            if tree_mode != TREE_MODE_STANDARD {
+                return hash;
+            }
+
+            // keccak256(bytes.concat(hash)), the OpenZeppelin StandardMerkleTree leaf
+            let mut leaf_input: Array<u256> = ArrayTrait::new();
+            leaf_input.append(hash);
+            let h = keccak_u256s_be_inputs(leaf_input.span());
+            u256 {
+                low: integer::u128_byte_reverse(h.high),
+                high: integer::u128_byte_reverse(h.low)
+            }
+        }
+
+        fn _consume_nullifier(
+            ref self: ContractState,
+            order_proof: OrderProof,
+            nullifiers_root: u256,
+            tree_mode: u8
+        ) {
+            // Leaf is keccak256(abi.encode(orderHash, nullifier)), binding the order to its fill
+            let mut leaf_input: Array<u256> = ArrayTrait::new();
+            leaf_input.append(order_proof.order_hash);
+            leaf_input.append(order_proof.nullifier);
+            let h = keccak_u256s_be_inputs(leaf_input.span());
+            let leaf = self
+                ._tree_leaf(
+                    u256 {
+                        low: integer::u128_byte_reverse(h.high),
+                        high: integer::u128_byte_reverse(h.low)
+                    },
+                    tree_mode
+                );
+
+            let valid = self._verify_merkle_proof(
+                order_proof.nullifier_proof,
+                nullifiers_root,
+                leaf,
+                order_proof.leaf_index
+            );
+            assert(valid, 'Invalid nullifier proof');
+
+            // A fill may only ever settle the order it was first proven for
+            let consumed_by = self.nullifier_mapping.entry(order_proof.nullifier).read();
+            assert(
+                consumed_by == 0 || consumed_by == order_proof.order_hash,
+                'Fill already used'
+            );
+            self.nullifier_mapping.entry(order_proof.nullifier).write(order_proof.order_hash);
         }
 
         fn _verify_merkle_proof(
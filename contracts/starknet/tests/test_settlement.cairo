@@ -20,6 +20,10 @@ fn setup() -> (ISettlementContractDispatcher, ContractAddress) {
     let mut constructor_calldata = array![];
     Serde::serialize(@roots.span(), ref constructor_calldata);
     Serde::serialize(@vk, ref constructor_calldata);
+    // Bankai Sepolia
+    Serde::serialize(@0_u8, ref constructor_calldata);
+    // starknet-sepolia in script/chains.toml
+    Serde::serialize(@393402133025997798_u64, ref constructor_calldata);
     
     let (contract_address, _) = contract.deploy(@constructor_calldata).unwrap();
     
@@ -40,8 +44,10 @@ fn test_submit_order() {
         source_chain_id: 1,
         destination_chain_id: 2,
         receiver: 0x123_u256,
+        token: 0_u256,
         amount: 1000_u256,
         block_number: 100,
+        fill_deadline: 100,
     };
     
     settlement.submit_order(order);
@@ -66,8 +72,10 @@ fn test_submit_order_wrong_chain() {
         source_chain_id: 2, // Different from actual chain ID
         destination_chain_id: 3,
         receiver: 0x123_u256,
+        token: 0_u256,
         amount: 1000_u256,
         block_number: 100,
+        fill_deadline: 100,
     };
     
     settlement.submit_order(order);
@@ -75,6 +83,24 @@ fn test_submit_order_wrong_chain() {
     stop_cheat_chain_id_global();
 }
 
+#[test]
+#[should_panic(expected: ('Invalid fill window',))]
+fn test_submit_order_deadline_before_block() {
+    let (settlement, _) = setup();
+
+    let order = Order {
+        source_chain_id: 2,
+        destination_chain_id: 3,
+        receiver: 0x123_u256,
+        token: 0_u256,
+        amount: 1000_u256,
+        block_number: 100,
+        fill_deadline: 99,
+    };
+
+    settlement.submit_order(order);
+}
+
 #[test]
 fn test_reset_orders() {
     let (settlement, _) = setup();
@@ -85,8 +111,10 @@ fn test_reset_orders() {
         source_chain_id: 1,
         destination_chain_id: 2,
         receiver: 0x123_u256,
+        token: 0_u256,
         amount: 1000_u256,
         block_number: 100,
+        fill_deadline: 100,
     };
     
     settlement.submit_order(order);
@@ -110,6 +138,18 @@ fn test_get_vk() {
     assert!(vk == 0x123456789abcdef, "VK should match constructor value");
 }
 
+#[test]
+fn test_initial_accumulator_root() {
+    let (settlement, _) = setup();
+
+    // settlement_lib::EMPTY_ACCUMULATOR_ROOT
+    let root = settlement.get_accumulator_root();
+    assert!(
+        root == 0x27ae5ba08d7291c96c8cbddcc148bf48a6d68c7974b94356f53754ef6171d757,
+        "Accumulator should start empty"
+    );
+}
+
 #[test]
 fn test_event_emission_on_submit() {
     let (settlement, contract_address) = setup();
@@ -122,8 +162,10 @@ fn test_event_emission_on_submit() {
         source_chain_id: 1,
         destination_chain_id: 2,
         receiver: 0x123_u256,
+        token: 0_u256,
         amount: 1000_u256,
         block_number: 100,
+        fill_deadline: 100,
     };
     
     settlement.submit_order(order);
@@ -149,22 +191,26 @@ fn test_order_hash_matches_evm() {
         source_chain_id: 84532,
         destination_chain_id: 11155111,
         receiver: 0x3A1D60A48B1104a31133dFBC70E8a589ce8dE57a_u256,
+        token: 0_u256,
         amount: 2500000000000000000_u256,
         block_number: 9452994,
+        fill_deadline: 9452994,
     };
     let h1 = settlement.hash_order(order1);
-    assert!(h1 == 0xd811c398160b6170623458b1e72c0405857dc075ec28135c78546aad5c8f148b, "hash mismatch: h1");
+    assert!(h1 == 0x2429686760020e2d7fd690afd1eb1e209110d34e6b1b79a5dd34e03434422286, "hash mismatch: h1");
 
     // Example 2 from proof.json (Base Sepolia)
     let order2 = Order {
         source_chain_id: 84532,
         destination_chain_id: 11155111,
         receiver: 0xDc720ddDF0dDAecF594804618507a62D86D96F9c_u256,
+        token: 0_u256,
         amount: 2500000000000000000_u256,
         block_number: 9452994,
+        fill_deadline: 9452994,
     };
     let h2 = settlement.hash_order(order2);
-    assert!(h2 == 0xf75ef566d2e46ecbda6bbf708d98f359267fc918d3c3d12680205b4b7a67f3d5, "hash mismatch: h2");
+    assert!(h2 == 0xe6089e3a7787da5708e0d323979e2cab4134023d714014cd68664c04fac4940a, "hash mismatch: h2");
 }
 
 #[test]
@@ -206,16 +252,20 @@ fn test_multiple_orders_same_chain() {
         source_chain_id: 84532,
         destination_chain_id: 11155111,
         receiver: 0x9eCD8efB5b592786b19cC776D58cD651B553e269,
+        token: 0_u256,
         amount: 2500000000000000000_u256,
         block_number: 9452270,
+        fill_deadline: 9452270,
     };
     
     let order2 = Order {
         source_chain_id: 84532,
         destination_chain_id: 11155111,
         receiver: 0xed5C648955a4157cbc66b74B0726BC761CfeeD2b_u256,
+        token: 0_u256,
         amount: 1715935090000000000_u256,
         block_number: 9453085,
+        fill_deadline: 9453085,
     };
     
     settlement.submit_order(order1);
@@ -225,8 +275,8 @@ fn test_multiple_orders_same_chain() {
     let hash1 = settlement.hash_order(order1);
     let hash2 = settlement.hash_order(order2);
     
-    let expected_hash1: u256 = 0x40429c3805cf9374550099262a01bf408ca7475edd2f38e1a2a7bf7b7cb7725c;
-    let expected_hash2: u256 = 0xc7e5b3e5385bd9b9b6e0807ce2550fb9e81649caee39021f6d21a7c12e2dacd4;
+    let expected_hash1: u256 = 0x261f4cb3318c4bbe62799cf45afe2f04a61f4ca6e6e2e8c4ea60481dbd21deb3;
+    let expected_hash2: u256 = 0x325c288096bab6ff92c02dd206ff0f8b287c90fbb035c227beecf250e9a79b80;
     
     assert!(hash1 == expected_hash1, "Order 1 hash must match");
     assert!(hash2 == expected_hash2, "Order 2 hash must match");
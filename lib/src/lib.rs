@@ -1,16 +1,114 @@
-use alloy_primitives::{keccak256, FixedBytes};
-use alloy_sol_types::{sol, SolType};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::RangeInclusive;
+use core::str::FromStr;
+
+use alloy_primitives::{b256, keccak256, Address, Bytes, FixedBytes, Log};
+use alloy_sol_types::{sol, SolEvent, SolType};
 use serde::{Deserialize, Serialize};
 
+/// Layout version of [`SettlementOutput`], bumped whenever the committed fields change.
+pub const SETTLEMENT_OUTPUT_VERSION: u8 = 6;
+
+/// Depth of the [`OrderAccumulator`] tree, fixing its capacity at 2^32 orders.
+pub const ACCUMULATOR_DEPTH: usize = 32;
+
+/// Root of an empty [`OrderAccumulator`], which every settlement contract starts from.
+pub const EMPTY_ACCUMULATOR_ROOT: FixedBytes<32> =
+    b256!("27ae5ba08d7291c96c8cbddcc148bf48a6d68c7974b94356f53754ef6171d757");
+
+/// Vkey of the settlement program that the Solana program verifies proofs against. Update it
+/// and redeploy whenever the program changes.
+pub const BANKAI_VKEY_HASH: &str =
+    "0x009c661e44c7e5e76f0aafdfab8ceb7c76357cc5ba5863a7dfa0b306807f8c02";
+
+/// Variants of the Solana program's `SettlementError` and their messages, in declaration order,
+/// so the n-th entry is Anchor error `6000 + n`. The program's tests check its enum against this
+/// table, and the CLI decodes failed transactions with it.
+pub const SOLANA_PROGRAM_ERRORS: [(&str, &str); 12] = [
+    ("InvalidProof", "Invalid Groth16 proof"),
+    ("InvalidPublicInputs", "Invalid public inputs layout"),
+    ("InvalidMerkleProof", "Invalid Merkle proof"),
+    ("InvalidOrderHash", "Order hash mismatch"),
+    ("InvalidNullifierProof", "Invalid nullifier proof"),
+    ("NullifierAlreadyUsed", "Fill already used by another order"),
+    (
+        "WrongBankaiNetwork",
+        "Proof is for a different Bankai network",
+    ),
+    (
+        "InvalidFillWindow",
+        "Fill deadline is before the order block",
+    ),
+    (
+        "AccumulatorRootMismatch",
+        "Proof does not extend the stored accumulator root",
+    ),
+    (
+        "WrongSettlementChain",
+        "Accumulator transition is for a different settlement chain",
+    ),
+    ("InvalidAccumulatorProof", "Invalid accumulator proof"),
+    ("StateAlreadyMigrated", "State account is already migrated"),
+];
+
 sol! {
+    /// An intent to receive `amount` of `token` on the destination chain.
+    /// A zero `token` address denotes a native ETH transfer.
+    ///
+    /// The fill must be included in a block between `block_number` and `fill_deadline`,
+    /// inclusive. Both are part of the order hash, so each order carries its own fill window.
     #[derive(Debug, Serialize, Deserialize)]
     struct Order {
         uint64 source_chain_id;
         uint64 destination_chain_id;
         address receiver;
+        #[serde(default)]
+        address token;
         uint256 amount;
         uint64 block_number;
+        uint64 fill_deadline;
     }
+
+    /// The public values committed by the settlement program.
+    /// Every field is a static type, so the ABI encoding is a flat sequence of 32-byte words.
+    #[derive(Debug, Serialize, Deserialize)]
+    struct SettlementOutput {
+        uint8 version;
+        uint8 tree_mode;
+        uint8 bankai_network;
+        bytes32 orders_root;
+        bytes32 nullifiers_root;
+        uint64 block_number;
+        bytes32 block_hash;
+        uint64 destination_chain_id;
+        uint64 order_count;
+        /// Root of the tree over this batch's [`AccumulatorTransition`]s, one per settlement chain
+        /// with orders in the batch
+        bytes32 accumulators_root;
+    }
+
+    /// How a batch moves one settlement chain's [`OrderAccumulator`]: its root before and after
+    /// the batch's orders from that chain are appended, and the chain's new order count.
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct AccumulatorTransition {
+        uint64 chain_id;
+        bytes32 previous_root;
+        bytes32 root;
+        uint64 count;
+    }
+
+    /// The ERC-20 `Transfer` event, used to prove token fills from receipt logs.
+    #[derive(Debug)]
+    event Transfer(address indexed from, address indexed to, uint256 value);
 }
 
 impl Order {
@@ -20,6 +118,87 @@ impl Order {
         let encoded = Order::abi_encode(self);
         keccak256(&encoded)
     }
+
+    /// Returns the inclusive `[block_number, fill_deadline]` range a fill transaction must be
+    /// included in. The range is empty if the deadline is before `block_number`.
+    pub fn fill_window(&self) -> RangeInclusive<u64> {
+        self.block_number..=self.fill_deadline
+    }
+
+    /// Returns true if the order is filled with native ETH rather than an ERC-20 token.
+    pub fn is_native(&self) -> bool {
+        self.token == Address::ZERO
+    }
+
+    /// Finds the ERC-20 `Transfer` log emitted by the order's token contract that pays
+    /// exactly `amount` to `receiver`.
+    pub fn find_transfer<'a>(&self, logs: &'a [Log]) -> Option<&'a Log> {
+        logs.iter().find(|log| {
+            log.address == self.token
+                && Transfer::decode_log(log)
+                    .map(|transfer| transfer.to == self.receiver && transfer.value == self.amount)
+                    .unwrap_or(false)
+        })
+    }
+
+    /// Returns true if the fill transaction's calldata ends with this order's hash: the whole
+    /// data of a native transfer, or appended after the arguments of an ERC-20 `transfer`.
+    /// Naming the order binds the fill's nullifier to it on every settlement chain, so the fill
+    /// can't settle an order from another source chain in a later batch.
+    pub fn is_named_by(&self, fill_input: &[u8]) -> bool {
+        fill_input.ends_with(self.hash().as_slice())
+    }
+}
+
+impl AccumulatorTransition {
+    /// Computes the Keccak256 hash of the transition.
+    /// This matches Solidity's `keccak256(abi.encode(transition))`.
+    pub fn hash(&self) -> FixedBytes<32> {
+        keccak256(AccumulatorTransition::abi_encode(self))
+    }
+}
+
+/// Returns the destination chain shared by every order in a batch.
+///
+/// One proof covers fills on a single chain and commits a single
+/// `SettlementOutput.destination_chain_id`, so a batch must be non-empty and all of its orders
+/// must be filled on the same chain. Orders for other destinations need a separate batch.
+pub fn batch_destination_chain_id(orders: &[Order]) -> Result<u64, String> {
+    let first = orders
+        .first()
+        .ok_or_else(|| "Batch has no orders".to_string())?;
+    let destination_chain_id = first.destination_chain_id;
+    if let Some((index, order)) = orders
+        .iter()
+        .enumerate()
+        .find(|(_, order)| order.destination_chain_id != destination_chain_id)
+    {
+        return Err(format!(
+            "Order {index}: destination chain {} differs from the batch's {destination_chain_id}; \
+             a batch can only settle fills on one destination chain",
+            order.destination_chain_id
+        ));
+    }
+    Ok(destination_chain_id)
+}
+
+impl SettlementOutput {
+    /// ABI-encodes the output, matching Solidity's `abi.encode(output)`.
+    pub fn encode(&self) -> Vec<u8> {
+        SettlementOutput::abi_encode(self)
+    }
+
+    /// Decodes the program's public values, rejecting unknown layout versions.
+    pub fn decode(public_values: &[u8]) -> Result<Self, alloy_sol_types::Error> {
+        let output = SettlementOutput::abi_decode(public_values)?;
+        if output.version != SETTLEMENT_OUTPUT_VERSION {
+            return Err(alloy_sol_types::Error::custom(format!(
+                "unsupported settlement output version {}, expected {SETTLEMENT_OUTPUT_VERSION}",
+                output.version
+            )));
+        }
+        Ok(output)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +207,72 @@ pub struct ClaimedExecution {
     pub tx_hash: FixedBytes<32>,
 }
 
+impl ClaimedExecution {
+    /// Computes the nullifier marking this fill transaction as consumed: `keccak256(tx_hash)`.
+    pub fn nullifier(&self) -> FixedBytes<32> {
+        keccak256(self.tx_hash)
+    }
+}
+
+/// Computes the nullifier tree leaf binding an order to the fill that settled it.
+/// This matches Solidity's `keccak256(abi.encode(orderHash, nullifier))`.
+pub fn nullifier_leaf(order_hash: FixedBytes<32>, nullifier: FixedBytes<32>) -> FixedBytes<32> {
+    let mut combined = Vec::with_capacity(64);
+    combined.extend_from_slice(order_hash.as_slice());
+    combined.extend_from_slice(nullifier.as_slice());
+    keccak256(&combined)
+}
+
+/// Layout of the orders and nullifier Merkle trees, committed as `SettlementOutput.tree_mode`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum MerkleTreeMode {
+    /// Raw hashes as leaves, duplicating the last node on odd levels.
+    #[default]
+    Legacy = 0,
+    /// OpenZeppelin's `StandardMerkleTree`: double-hashed leaves, sorted and laid out as a
+    /// complete binary tree, so an internal node can never be passed off as a leaf.
+    Standard = 1,
+}
+
+impl MerkleTreeMode {
+    /// Computes the tree leaf for an order (or nullifier leaf) hash.
+    /// In standard mode this matches Solidity's `keccak256(bytes.concat(hash))`.
+    pub fn leaf(self, hash: FixedBytes<32>) -> FixedBytes<32> {
+        match self {
+            MerkleTreeMode::Legacy => hash,
+            MerkleTreeMode::Standard => keccak256(hash),
+        }
+    }
+}
+
+impl TryFrom<u8> for MerkleTreeMode {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MerkleTreeMode::Legacy),
+            1 => Ok(MerkleTreeMode::Standard),
+            _ => Err(format!("Unknown merkle tree mode: {value}")),
+        }
+    }
+}
+
+impl FromStr for MerkleTreeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "legacy" => Ok(MerkleTreeMode::Legacy),
+            "standard" => Ok(MerkleTreeMode::Standard),
+            _ => Err(format!(
+                "Unknown merkle tree mode: {s}. Supported: legacy, standard"
+            )),
+        }
+    }
+}
+
 /// Hash a pair of nodes, matching OpenZeppelin's commutativeKeccak256 behavior
 fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
     let mut combined = Vec::with_capacity(64);
@@ -54,128 +299,1286 @@ pub struct OrderProof {
 pub struct MerkleTreeData {
     pub root: FixedBytes<32>,
     pub proofs: Vec<OrderProof>,
+    #[serde(default)]
+    pub mode: MerkleTreeMode,
 }
 
-/// Generates a Merkle root from an array of orders matching OpenZeppelin's implementation
-pub fn generate_merkle_root(orders: &[Order]) -> FixedBytes<32> {
-    if orders.is_empty() {
-        return FixedBytes::ZERO;
+/// Nodes of a built tree, in the layout of its [`MerkleTreeMode`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Nodes {
+    /// Every level bottom-up, from the leaves to the root
+    Layers(Vec<Vec<[u8; 32]>>),
+    /// OpenZeppelin's flat array with the root first, and the array index of each leaf
+    Flat {
+        tree: Vec<[u8; 32]>,
+        positions: Vec<usize>,
+    },
+}
+
+/// A Merkle tree whose nodes are built once, so every proof is a lookup instead of a rebuild
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    mode: MerkleTreeMode,
+    leaves: Vec<FixedBytes<32>>,
+    nodes: Nodes,
+}
+
+impl MerkleTree {
+    /// Builds a tree over order hashes (or [`nullifier_leaf`]s) in the given layout
+    pub fn new(hashes: &[FixedBytes<32>], mode: MerkleTreeMode) -> Self {
+        let leaves: Vec<FixedBytes<32>> = hashes.iter().map(|hash| mode.leaf(*hash)).collect();
+        let raw: Vec<[u8; 32]> = leaves.iter().map(|leaf| leaf.0).collect();
+        let nodes = match mode {
+            MerkleTreeMode::Legacy => Nodes::Layers(build_layers(raw)),
+            MerkleTreeMode::Standard => Nodes::Flat {
+                tree: build_standard_tree(&raw),
+                positions: standard_leaf_positions(&raw),
+            },
+        };
+
+        Self {
+            mode,
+            leaves,
+            nodes,
+        }
+    }
+
+    /// Builds a tree over the hashes of `orders`
+    pub fn from_orders(orders: &[Order], mode: MerkleTreeMode) -> Self {
+        let hashes: Vec<FixedBytes<32>> = orders.iter().map(Order::hash).collect();
+        Self::new(&hashes, mode)
+    }
+
+    pub fn mode(&self) -> MerkleTreeMode {
+        self.mode
     }
 
-    let mut hashes: Vec<[u8; 32]> = orders.iter().map(|order| order.hash().0).collect();
+    /// The tree leaves in insertion order, as computed by [`MerkleTreeMode::leaf`]
+    pub fn leaves(&self) -> &[FixedBytes<32>] {
+        &self.leaves
+    }
+
+    /// The tree root, or zero for an empty tree
+    pub fn root(&self) -> FixedBytes<32> {
+        let root = match &self.nodes {
+            Nodes::Layers(layers) => layers.last().and_then(|layer| layer.first()),
+            Nodes::Flat { tree, .. } => tree.first(),
+        };
+        root.copied().unwrap_or_default().into()
+    }
+
+    /// Sibling path for the leaf at `index`, empty if the index is out of range
+    pub fn proof(&self, index: usize) -> Vec<FixedBytes<32>> {
+        if index >= self.leaves.len() {
+            return vec![];
+        }
 
-    while hashes.len() > 1 {
-        let mut next_level = Vec::new();
+        match &self.nodes {
+            Nodes::Layers(layers) => {
+                let mut position = index;
+                layers[..layers.len() - 1]
+                    .iter()
+                    .map(|layer| {
+                        // Odd number of nodes, the last one is paired with itself
+                        let sibling = layer.get(position ^ 1).unwrap_or(&layer[position]);
+                        position /= 2;
+                        FixedBytes::from(*sibling)
+                    })
+                    .collect()
+            }
+            Nodes::Flat { tree, positions } => {
+                let mut position = positions[index];
+                let mut proof = Vec::new();
+                while position > 0 {
+                    proof.push(FixedBytes::from(tree[sibling_index(position)]));
+                    position = (position - 1) / 2;
+                }
+                proof
+            }
+        }
+    }
+
+    /// Generates a multiproof for the leaves at `indices`.
+    /// Returns `None` for legacy trees, or if an index is out of range or repeated.
+    pub fn multiproof(&self, indices: &[usize]) -> Option<MultiProof> {
+        let Nodes::Flat { tree, positions } = &self.nodes else {
+            return None;
+        };
+        if tree.is_empty() {
+            return None;
+        }
+
+        // leaves are consumed from the deepest tree index upwards
+        let mut proven: Vec<(usize, usize)> = Vec::with_capacity(indices.len());
+        for &index in indices {
+            proven.push((*positions.get(index)?, index));
+        }
+        proven.sort_by_key(|(position, _)| core::cmp::Reverse(*position));
+        if proven.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return None;
+        }
 
-        for i in (0..hashes.len()).step_by(2) {
-            if i + 1 < hashes.len() {
-                next_level.push(hash_pair(&hashes[i], &hashes[i + 1]));
+        let mut stack: VecDeque<usize> = proven.iter().map(|(position, _)| *position).collect();
+        let mut proof = Vec::new();
+        let mut proof_flags = Vec::new();
+        while let Some(position) = stack.pop_front() {
+            if position == 0 {
+                break;
+            }
+            let sibling = sibling_index(position);
+            if stack.front() == Some(&sibling) {
+                proof_flags.push(true);
+                stack.pop_front();
             } else {
-                next_level.push(hash_pair(&hashes[i], &hashes[i]));
+                proof_flags.push(false);
+                proof.push(FixedBytes::from(tree[sibling]));
             }
+            stack.push_back((position - 1) / 2);
+        }
+        if proven.is_empty() {
+            proof.push(FixedBytes::from(tree[0]));
         }
 
-        hashes = next_level;
+        Some(MultiProof {
+            leaf_indices: proven.into_iter().map(|(_, index)| index).collect(),
+            proof,
+            proof_flags,
+        })
     }
+}
+
+/// Generates a Merkle root from an array of orders matching OpenZeppelin's implementation
+pub fn generate_merkle_root(orders: &[Order]) -> FixedBytes<32> {
+    generate_merkle_root_with_mode(orders, MerkleTreeMode::Legacy)
+}
 
-    FixedBytes::from_slice(&hashes[0])
+/// Generates a Merkle root from an array of orders using the given tree layout
+pub fn generate_merkle_root_with_mode(orders: &[Order], mode: MerkleTreeMode) -> FixedBytes<32> {
+    MerkleTree::from_orders(orders, mode).root()
 }
 
 /// Generates Merkle proofs for all orders matching OpenZeppelin's implementation
 pub fn generate_all_proofs(orders: &[Order]) -> MerkleTreeData {
-    if orders.is_empty() {
-        return MerkleTreeData {
-            root: FixedBytes::ZERO,
-            proofs: vec![],
-        };
-    }
-
-    let leaves: Vec<[u8; 32]> = orders.iter().map(|order| order.hash().0).collect();
+    generate_all_proofs_with_mode(orders, MerkleTreeMode::Legacy)
+}
 
-    // Build the tree and collect proofs
-    let root = build_tree_and_get_root(&leaves);
+/// Generates Merkle proofs for all orders using the given tree layout
+pub fn generate_all_proofs_with_mode(orders: &[Order], mode: MerkleTreeMode) -> MerkleTreeData {
+    let tree = MerkleTree::from_orders(orders, mode);
     let proofs: Vec<OrderProof> = orders
         .iter()
         .enumerate()
-        .map(|(index, order)| {
-            let proof_hashes = generate_proof(&leaves, index);
-            OrderProof {
-                order: order.clone(),
-                proof: proof_hashes
-                    .iter()
-                    .map(|hash| FixedBytes::<32>::from_slice(hash))
-                    .collect(),
-                leaf_index: index,
-            }
+        .map(|(index, order)| OrderProof {
+            order: order.clone(),
+            proof: tree.proof(index),
+            leaf_index: index,
         })
         .collect();
 
     MerkleTreeData {
-        root: root.into(),
+        root: tree.root(),
+        proofs,
+        mode,
+    }
+}
+
+/// Proof that an order was settled by the fill with the given nullifier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NullifierProof {
+    pub nullifier: FixedBytes<32>,
+    pub proof: Vec<FixedBytes<32>>,
+    pub leaf_index: usize,
+}
+
+/// Complete nullifier tree data including root and all proofs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NullifierTreeData {
+    pub root: FixedBytes<32>,
+    pub proofs: Vec<NullifierProof>,
+}
+
+fn nullifier_leaves(orders: &[Order], claims: &[ClaimedExecution]) -> Vec<FixedBytes<32>> {
+    assert_eq!(
+        orders.len(),
+        claims.len(),
+        "Expected one claimed execution per order"
+    );
+    orders
+        .iter()
+        .zip(claims)
+        .map(|(order, claim)| nullifier_leaf(order.hash(), claim.nullifier()))
+        .collect()
+}
+
+/// Generates the nullifier Merkle root, with leaves in the same order as the orders tree
+pub fn generate_nullifier_root(
+    orders: &[Order],
+    claims: &[ClaimedExecution],
+    mode: MerkleTreeMode,
+) -> FixedBytes<32> {
+    MerkleTree::new(&nullifier_leaves(orders, claims), mode).root()
+}
+
+/// Generates nullifier Merkle proofs for all orders, indexed like [`generate_all_proofs`]
+pub fn generate_all_nullifier_proofs(
+    orders: &[Order],
+    claims: &[ClaimedExecution],
+    mode: MerkleTreeMode,
+) -> NullifierTreeData {
+    let tree = MerkleTree::new(&nullifier_leaves(orders, claims), mode);
+    let proofs = claims
+        .iter()
+        .enumerate()
+        .map(|(index, claim)| NullifierProof {
+            nullifier: claim.nullifier(),
+            proof: tree.proof(index),
+            leaf_index: index,
+        })
+        .collect();
+
+    NullifierTreeData {
+        root: tree.root(),
         proofs,
     }
 }
 
-/// Build the Merkle tree and return the root
-fn build_tree_and_get_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+/// Build every level of a legacy tree, duplicating the last node on odd levels
+fn build_layers(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
     if leaves.is_empty() {
-        return [0u8; 32];
+        return vec![];
     }
 
-    let mut hashes = leaves.to_vec();
+    let mut layers = vec![leaves];
+    while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+        let next_level = layer
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], &pair[pair.len() - 1]))
+            .collect();
+        layers.push(next_level);
+    }
 
-    while hashes.len() > 1 {
-        let mut next_level = Vec::new();
-        for i in (0..hashes.len()).step_by(2) {
-            if i + 1 < hashes.len() {
-                next_level.push(hash_pair(&hashes[i], &hashes[i + 1]));
-            } else {
-                next_level.push(hash_pair(&hashes[i], &hashes[i]));
+    layers
+}
+
+/// Build an OpenZeppelin `StandardMerkleTree` as a flat array, with the root at index 0
+/// and the sorted leaves stored in reverse at the end of the array
+fn build_standard_tree(leaves: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    if leaves.is_empty() {
+        return vec![];
+    }
+
+    let mut sorted = leaves.to_vec();
+    sorted.sort();
+
+    let mut tree = vec![[0u8; 32]; 2 * sorted.len() - 1];
+    let last = tree.len() - 1;
+    for (i, leaf) in sorted.iter().enumerate() {
+        tree[last - i] = *leaf;
+    }
+    for i in (0..sorted.len() - 1).rev() {
+        tree[i] = hash_pair(&tree[2 * i + 1], &tree[2 * i + 2]);
+    }
+
+    tree
+}
+
+/// Tree index of each (unsorted) leaf in the `StandardMerkleTree` array layout
+fn standard_leaf_positions(leaves: &[[u8; 32]]) -> Vec<usize> {
+    if leaves.is_empty() {
+        return vec![];
+    }
+
+    let mut ranked: Vec<usize> = (0..leaves.len()).collect();
+    ranked.sort_by_key(|&i| leaves[i]);
+
+    let last = 2 * leaves.len() - 2;
+    let mut positions = vec![0; leaves.len()];
+    for (rank, &i) in ranked.iter().enumerate() {
+        positions[i] = last - rank;
+    }
+    positions
+}
+
+/// Array index of a node's sibling in the `StandardMerkleTree` layout
+fn sibling_index(position: usize) -> usize {
+    if position % 2 == 1 {
+        position + 1
+    } else {
+        position - 1
+    }
+}
+
+/// Append-only Merkle accumulator over every order ever proven, like the Ethereum deposit
+/// contract tree: only the frontier (one pending node per level) is kept, so the state is
+/// small enough to persist between runs and to extend inside the zkVM.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderAccumulator {
+    /// Left-hand nodes still waiting for their right sibling, one per level
+    pub branch: [FixedBytes<32>; ACCUMULATOR_DEPTH],
+    /// Number of orders appended so far
+    pub count: u64,
+}
+
+impl Default for OrderAccumulator {
+    fn default() -> Self {
+        Self {
+            branch: [FixedBytes::ZERO; ACCUMULATOR_DEPTH],
+            count: 0,
+        }
+    }
+}
+
+impl OrderAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an order hash, leafed like [`MerkleTreeMode::Standard`]
+    pub fn append(&mut self, order_hash: FixedBytes<32>) {
+        assert!(
+            self.count < 1 << ACCUMULATOR_DEPTH,
+            "Order accumulator is full"
+        );
+
+        self.count += 1;
+        let mut size = self.count;
+        let mut node = MerkleTreeMode::Standard.leaf(order_hash).0;
+        for height in 0..ACCUMULATOR_DEPTH {
+            if size & 1 == 1 {
+                self.branch[height] = node.into();
+                return;
             }
+            node = hash_pair(&self.branch[height].0, &node);
+            size /= 2;
+        }
+    }
+
+    /// Appends the hashes of `orders`, in order
+    pub fn extend(&mut self, orders: &[Order]) {
+        for order in orders {
+            self.append(order.hash());
         }
-        hashes = next_level;
     }
 
-    hashes[0]
+    /// The root of the depth-[`ACCUMULATOR_DEPTH`] tree, with empty leaves as zero
+    pub fn root(&self) -> FixedBytes<32> {
+        let mut zero = [0u8; 32];
+        let mut node = [0u8; 32];
+        let mut size = self.count;
+        for height in 0..ACCUMULATOR_DEPTH {
+            node = if size & 1 == 1 {
+                hash_pair(&self.branch[height].0, &node)
+            } else {
+                hash_pair(&node, &zero)
+            };
+            zero = hash_pair(&zero, &zero);
+            size /= 2;
+        }
+        node.into()
+    }
 }
 
-/// Generate a Merkle proof for a leaf at the given index
-fn generate_proof(leaves: &[[u8; 32]], index: usize) -> Vec<[u8; 32]> {
-    if leaves.is_empty() || index >= leaves.len() {
-        return vec![];
+/// One [`OrderAccumulator`] per settlement chain, keyed by chain id.
+///
+/// Orders settle on their source chain, so each order is appended to its source chain's
+/// accumulator. A chain's root only moves in batches holding its orders, and a chain that
+/// skips a batch can still settle the next one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChainAccumulators(pub BTreeMap<u64, OrderAccumulator>);
+
+impl ChainAccumulators {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    let mut proof = Vec::new();
-    let mut hashes = leaves.to_vec();
-    let mut current_index = index;
+    /// The accumulator of `chain_id`, empty if none of its orders were proven yet
+    pub fn get(&self, chain_id: u64) -> OrderAccumulator {
+        self.0.get(&chain_id).cloned().unwrap_or_default()
+    }
 
-    while hashes.len() > 1 {
-        let mut next_level = Vec::new();
+    /// Appends each order to its source chain's accumulator, returning the transition of every
+    /// chain with orders in the batch, ordered by chain id
+    pub fn extend(&mut self, orders: &[Order]) -> Vec<AccumulatorTransition> {
+        let mut by_chain: BTreeMap<u64, Vec<FixedBytes<32>>> = BTreeMap::new();
+        for order in orders {
+            by_chain
+                .entry(order.source_chain_id)
+                .or_default()
+                .push(order.hash());
+        }
 
-        for i in (0..hashes.len()).step_by(2) {
-            if i + 1 < hashes.len() {
-                // If this pair contains our current index, add the sibling to the proof
-                if i == current_index || i + 1 == current_index {
-                    let sibling = if i == current_index {
-                        hashes[i + 1]
-                    } else {
-                        hashes[i]
-                    };
-                    proof.push(sibling);
+        by_chain
+            .into_iter()
+            .map(|(chain_id, hashes)| {
+                let accumulator = self.0.entry(chain_id).or_default();
+                let previous_root = accumulator.root();
+                for hash in hashes {
+                    accumulator.append(hash);
                 }
-                next_level.push(hash_pair(&hashes[i], &hashes[i + 1]));
+                AccumulatorTransition {
+                    chain_id,
+                    previous_root,
+                    root: accumulator.root(),
+                    count: accumulator.count,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Builds the tree committed as `SettlementOutput.accumulators_root` over a batch's transitions
+pub fn accumulator_transitions_tree(
+    transitions: &[AccumulatorTransition],
+    mode: MerkleTreeMode,
+) -> MerkleTree {
+    let hashes: Vec<FixedBytes<32>> = transitions
+        .iter()
+        .map(AccumulatorTransition::hash)
+        .collect();
+    MerkleTree::new(&hashes, mode)
+}
+
+/// Reconstructs the root from a leaf and its sibling path, matching OpenZeppelin's
+/// `MerkleProof.processProof`
+pub fn process_proof(leaf: FixedBytes<32>, proof: &[FixedBytes<32>]) -> FixedBytes<32> {
+    proof
+        .iter()
+        .fold(leaf.0, |computed, sibling| hash_pair(&computed, &sibling.0))
+        .into()
+}
+
+/// Checks that `order` is included under `root`, as the settlement contracts do on-chain.
+///
+/// Sorted-pair hashing makes the path itself position-independent, but a legacy path still
+/// has one sibling per level, so `leaf_index` must be addressable by a path of that length.
+pub fn verify_order_proof(
+    order: &Order,
+    proof: &[FixedBytes<32>],
+    leaf_index: usize,
+    root: FixedBytes<32>,
+    mode: MerkleTreeMode,
+) -> bool {
+    if mode == MerkleTreeMode::Legacy
+        && leaf_index.checked_shr(proof.len() as u32).unwrap_or(0) != 0
+    {
+        return false;
+    }
+    process_proof(mode.leaf(order.hash()), proof) == root
+}
+
+/// Checks that the nullifier leaf binding `order_hash` to `nullifier` is included under `root`
+pub fn verify_nullifier_proof(
+    order_hash: FixedBytes<32>,
+    nullifier: FixedBytes<32>,
+    proof: &[FixedBytes<32>],
+    root: FixedBytes<32>,
+    mode: MerkleTreeMode,
+) -> bool {
+    process_proof(mode.leaf(nullifier_leaf(order_hash, nullifier)), proof) == root
+}
+
+/// A single proof for several leaves of a `StandardMerkleTree`, verifiable with
+/// OpenZeppelin's `MerkleProof.multiProofVerify`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiProof {
+    /// Original index of each proven leaf, in the order the verifier consumes them
+    pub leaf_indices: Vec<usize>,
+    pub proof: Vec<FixedBytes<32>>,
+    pub proof_flags: Vec<bool>,
+}
+
+/// Reconstructs the root from a multiproof, matching OpenZeppelin's `processMultiProof`.
+/// `leaves` must already be tree leaves, ordered as in [`MultiProof::leaf_indices`].
+pub fn process_multiproof(
+    leaves: &[FixedBytes<32>],
+    proof: &[FixedBytes<32>],
+    proof_flags: &[bool],
+) -> Option<FixedBytes<32>> {
+    if leaves.len() + proof.len() != proof_flags.len() + 1 {
+        return None;
+    }
+
+    let mut hashes: Vec<[u8; 32]> = Vec::with_capacity(proof_flags.len());
+    let mut leaf_pos = 0;
+    let mut hash_pos = 0;
+    let mut proof_pos = 0;
+    for &flag in proof_flags {
+        let mut next = || {
+            if leaf_pos < leaves.len() {
+                leaf_pos += 1;
+                Some(leaves[leaf_pos - 1].0)
             } else {
-                // Odd number of nodes, duplicate the last one
-                if i == current_index {
-                    proof.push(hashes[i]);
+                hash_pos += 1;
+                hashes.get(hash_pos - 1).copied()
+            }
+        };
+        let a = next()?;
+        let b = if flag {
+            next()?
+        } else {
+            proof_pos += 1;
+            proof.get(proof_pos - 1)?.0
+        };
+        hashes.push(hash_pair(&a, &b));
+    }
+
+    if let Some(root) = hashes.last() {
+        (proof_pos == proof.len()).then(|| FixedBytes::from(*root))
+    } else {
+        leaves.first().or(proof.first()).copied()
+    }
+}
+
+/// Schema version of [`SettlementArtifact`], bumped whenever its fields change.
+pub const SETTLEMENT_ARTIFACT_VERSION: u32 = 3;
+
+/// The SP1 proof type a settlement was proven with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofSystem {
+    #[default]
+    Groth16,
+    Plonk,
+    /// Recursively compressed STARK, only verifiable off-chain
+    Compressed,
+    /// Uncompressed STARK shards, only verifiable off-chain
+    Core,
+}
+
+impl ProofSystem {
+    /// Whether proofs of this system can be verified on-chain, and so settle anything
+    pub fn settles_on_chain(self) -> bool {
+        matches!(self, ProofSystem::Groth16 | ProofSystem::Plonk)
+    }
+}
+
+impl FromStr for ProofSystem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "groth16" => Ok(ProofSystem::Groth16),
+            "plonk" => Ok(ProofSystem::Plonk),
+            "compressed" => Ok(ProofSystem::Compressed),
+            "core" => Ok(ProofSystem::Core),
+            _ => Err(format!(
+                "Unknown proof system: {s}. Supported: groth16, plonk, compressed, core"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ProofSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProofSystem::Groth16 => "groth16",
+            ProofSystem::Plonk => "plonk",
+            ProofSystem::Compressed => "compressed",
+            ProofSystem::Core => "core",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The Bankai network whose light client state the settlement was proven against, committed
+/// as `SettlementOutput.bankai_network`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum BankaiNetwork {
+    #[default]
+    Sepolia = 0,
+    Mainnet = 1,
+}
+
+impl BankaiNetwork {
+    /// Chain id of the Ethereum network Bankai attests to, which every fill must be sent on.
+    pub fn chain_id(self) -> u64 {
+        match self {
+            BankaiNetwork::Sepolia => 11155111,
+            BankaiNetwork::Mainnet => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for BankaiNetwork {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(BankaiNetwork::Sepolia),
+            1 => Ok(BankaiNetwork::Mainnet),
+            _ => Err(format!("Unknown Bankai network: {value}")),
+        }
+    }
+}
+
+impl FromStr for BankaiNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sepolia" => Ok(BankaiNetwork::Sepolia),
+            "mainnet" => Ok(BankaiNetwork::Mainnet),
+            _ => Err(format!(
+                "Unknown Bankai network: {s}. Supported: sepolia, mainnet"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BankaiNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BankaiNetwork::Sepolia => "sepolia",
+            BankaiNetwork::Mainnet => "mainnet",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Merkle and nullifier proofs for one order in a [`SettlementArtifact`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactOrderProof {
+    pub order: Order,
+    pub order_hash: FixedBytes<32>,
+    pub proof: Vec<FixedBytes<32>>,
+    pub leaf_index: usize,
+    pub nullifier: FixedBytes<32>,
+    pub nullifier_proof: Vec<FixedBytes<32>>,
+}
+
+impl ArtifactOrderProof {
+    /// Fails if `order_hash` isn't the hash of `order`, so a tampered proof file is caught
+    /// before it is sent instead of reverting on chain.
+    pub fn check_order_hash(&self) -> Result<(), String> {
+        let expected = self.order.hash();
+        if self.order_hash != expected {
+            return Err(format!(
+                "Order hash {} doesn't match its order, which hashes to {expected}",
+                self.order_hash
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A settlement chain's [`AccumulatorTransition`] in a [`SettlementArtifact`], with its proof
+/// against `SettlementOutput.accumulators_root`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccumulatorProof {
+    pub transition: AccumulatorTransition,
+    pub proof: Vec<FixedBytes<32>>,
+}
+
+/// Everything a settlement run produces (`proof.json`): the SP1 proof and its public values,
+/// plus the per-order proofs each source chain needs to settle against the committed roots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementArtifact {
+    /// Must equal [`SETTLEMENT_ARTIFACT_VERSION`]
+    pub version: u32,
+    pub proof_system: ProofSystem,
+    /// On-chain proof encoding; empty for STARK and mock proofs
+    pub proof: Bytes,
+    /// Full SP1 proof saved alongside for proof systems without an on-chain encoding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_file: Option<String>,
+    /// Set for mock proofs, which can only be used for dry runs
+    #[serde(default)]
+    pub mock: bool,
+    /// Verifying key hash of the settlement program
+    pub vkey: FixedBytes<32>,
+    pub public_values: Bytes,
+    pub bankai_network: BankaiNetwork,
+    pub block_number: u64,
+    pub block_hash: FixedBytes<32>,
+    pub tree_mode: MerkleTreeMode,
+    pub merkle_root: FixedBytes<32>,
+    pub nullifiers_root: FixedBytes<32>,
+    pub proofs_by_source_chain: BTreeMap<u64, Vec<ArtifactOrderProof>>,
+    /// Accumulator transition of every settlement chain with orders in the batch, keyed by
+    /// chain id
+    pub accumulators: BTreeMap<u64, AccumulatorProof>,
+    /// Proof calldata re-encoded for settlement chains whose verifier can't take the raw
+    /// proof, keyed by chain id (e.g. Garaga felts for Starknet)
+    #[serde(default)]
+    pub calldata: BTreeMap<u64, Vec<FixedBytes<32>>>,
+}
+
+impl SettlementArtifact {
+    /// Rejects artifacts written with a different schema version.
+    pub fn check_version(&self) -> Result<(), String> {
+        if self.version != SETTLEMENT_ARTIFACT_VERSION {
+            return Err(format!(
+                "unsupported proof file version {}, expected {SETTLEMENT_ARTIFACT_VERSION}",
+                self.version
+            ));
+        }
+        Ok(())
+    }
+
+    /// Decodes the committed public values and checks they match the artifact's roots.
+    pub fn settlement_output(&self) -> Result<SettlementOutput, String> {
+        let output = SettlementOutput::decode(&self.public_values).map_err(|e| e.to_string())?;
+        if output.orders_root != self.merkle_root {
+            return Err(format!(
+                "Public values commit root {}, but proof file lists {}",
+                output.orders_root, self.merkle_root
+            ));
+        }
+        if output.nullifiers_root != self.nullifiers_root {
+            return Err(format!(
+                "Public values commit nullifiers root {}, but proof file lists {}",
+                output.nullifiers_root, self.nullifiers_root
+            ));
+        }
+        if output.tree_mode != self.tree_mode as u8 {
+            return Err(format!(
+                "Public values commit tree mode {}, but proof file lists {:?}",
+                output.tree_mode, self.tree_mode
+            ));
+        }
+        if output.bankai_network != self.bankai_network as u8 {
+            return Err(format!(
+                "Public values commit Bankai network {}, but proof file lists {}",
+                output.bankai_network, self.bankai_network
+            ));
+        }
+        for (chain_id, accumulator) in &self.accumulators {
+            if accumulator.transition.chain_id != *chain_id {
+                return Err(format!(
+                    "Accumulator transition listed for chain {chain_id} is for chain {}",
+                    accumulator.transition.chain_id
+                ));
+            }
+            let leaf = self.tree_mode.leaf(accumulator.transition.hash());
+            if process_proof(leaf, &accumulator.proof) != output.accumulators_root {
+                return Err(format!(
+                    "Accumulator transition of chain {chain_id} isn't committed in the public values"
+                ));
+            }
+        }
+        if let Some(chain_id) = self
+            .proofs_by_source_chain
+            .keys()
+            .find(|chain_id| !self.accumulators.contains_key(chain_id))
+        {
+            return Err(format!(
+                "Proof file has orders for chain {chain_id} but no accumulator transition"
+            ));
+        }
+        Ok(output)
+    }
+
+    /// Fails unless the proof was generated with one of the proof systems `verifier` supports.
+    pub fn require_proof_system(
+        &self,
+        verifier: &str,
+        supported: &[ProofSystem],
+    ) -> Result<(), String> {
+        if supported.contains(&self.proof_system) {
+            return Ok(());
+        }
+        let supported: Vec<String> = supported.iter().map(|system| system.to_string()).collect();
+        Err(format!(
+            "{verifier} can only verify {} proofs, but the proof file holds a {} proof",
+            supported.join(" or "),
+            self.proof_system
+        ))
+    }
+
+    /// Accumulator transition of `chain_id`, if the batch has orders that settle on it
+    pub fn accumulator_for(&self, chain_id: u64) -> Option<&AccumulatorProof> {
+        self.accumulators.get(&chain_id)
+    }
+
+    /// Order proofs for orders that settle on `source_chain_id`
+    pub fn orders_for(&self, source_chain_id: u64) -> &[ArtifactOrderProof] {
+        self.proofs_by_source_chain
+            .get(&source_chain_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    const STANDARD_5: &str =
+        include_str!("../../contracts/evm/src/fixtures/merkle_proofs_standard_5.json");
+    const LEGACY_2: &str = include_str!("../../contracts/evm/src/fixtures/merkle_proofs.json");
+    const LEGACY_3: &str = include_str!("../../contracts/evm/src/fixtures/merkle_proofs_3.json");
+    const LEGACY_5: &str = include_str!("../../contracts/evm/src/fixtures/merkle_proofs_5.json");
+    const MULTIPROOF_STANDARD_5: &str =
+        include_str!("../../contracts/evm/src/fixtures/merkle_multiproof_standard_5.json");
+
+    /// `merkle_multiproof_standard_5.json`, as consumed by `settleOrdersMultiproof`
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct MultiProofFixture {
+        leaf_indices: Vec<usize>,
+        order_hashes: Vec<FixedBytes<32>>,
+        proof: Vec<FixedBytes<32>>,
+        proof_flags: Vec<bool>,
+        root: FixedBytes<32>,
+    }
+
+    fn fixture_orders(json: &str) -> (MerkleTreeData, Vec<Order>) {
+        let data: MerkleTreeData = serde_json::from_str(json).unwrap();
+        let orders = data
+            .proofs
+            .iter()
+            .map(|proof| proof.order.clone())
+            .collect();
+        (data, orders)
+    }
+
+    fn order(i: u64) -> Order {
+        Order {
+            source_chain_id: 84532,
+            destination_chain_id: 11155111,
+            receiver: Address::with_last_byte(i as u8),
+            token: Address::ZERO,
+            amount: U256::from(1_000 + i),
+            block_number: 100 + i,
+            fill_deadline: 100 + i,
+        }
+    }
+
+    #[test]
+    fn standard_tree_matches_fixture() {
+        let (data, orders) = fixture_orders(STANDARD_5);
+        assert_eq!(data.mode, MerkleTreeMode::Standard);
+
+        let generated = generate_all_proofs_with_mode(&orders, MerkleTreeMode::Standard);
+        assert_eq!(generated.root, data.root);
+        assert_eq!(
+            generate_merkle_root_with_mode(&orders, MerkleTreeMode::Standard),
+            data.root
+        );
+        for (generated, expected) in generated.proofs.iter().zip(&data.proofs) {
+            assert_eq!(generated.leaf_index, expected.leaf_index);
+            assert_eq!(generated.proof, expected.proof);
+        }
+    }
+
+    #[test]
+    fn legacy_tree_matches_fixtures() {
+        for json in [LEGACY_2, LEGACY_3, LEGACY_5] {
+            let (data, orders) = fixture_orders(json);
+            let generated = generate_all_proofs(&orders);
+            assert_eq!(generated.root, data.root);
+            assert_eq!(generate_merkle_root(&orders), data.root);
+            for (generated, expected) in generated.proofs.iter().zip(&data.proofs) {
+                assert_eq!(generated.leaf_index, expected.leaf_index);
+                assert_eq!(generated.proof, expected.proof);
+            }
+        }
+    }
+
+    #[test]
+    fn tree_proofs_fold_to_root() {
+        for mode in [MerkleTreeMode::Legacy, MerkleTreeMode::Standard] {
+            assert_eq!(MerkleTree::new(&[], mode).root(), FixedBytes::ZERO);
+
+            for size in 1..=9 {
+                let orders: Vec<Order> = (0..size).map(order).collect();
+                let tree = MerkleTree::from_orders(&orders, mode);
+                for (index, leaf) in tree.leaves().iter().enumerate() {
+                    assert_eq!(
+                        process_proof(*leaf, &tree.proof(index)),
+                        tree.root(),
+                        "{mode:?} tree of {size}, leaf {index}"
+                    );
                 }
-                next_level.push(hash_pair(&hashes[i], &hashes[i]));
+                assert!(tree.proof(size as usize).is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn multiproof_matches_fixture() {
+        let (_, orders) = fixture_orders(STANDARD_5);
+        let fixture: MultiProofFixture = serde_json::from_str(MULTIPROOF_STANDARD_5).unwrap();
+
+        let tree = MerkleTree::from_orders(&orders, MerkleTreeMode::Standard);
+        let multiproof = tree.multiproof(&[0, 2, 3]).unwrap();
+        assert_eq!(tree.root(), fixture.root);
+        assert_eq!(multiproof.leaf_indices, fixture.leaf_indices);
+        assert_eq!(multiproof.proof, fixture.proof);
+        assert_eq!(multiproof.proof_flags, fixture.proof_flags);
+
+        let hashes: Vec<FixedBytes<32>> = fixture
+            .leaf_indices
+            .iter()
+            .map(|&index| orders[index].hash())
+            .collect();
+        assert_eq!(hashes, fixture.order_hashes);
+
+        let leaves: Vec<FixedBytes<32>> = hashes
+            .iter()
+            .map(|hash| MerkleTreeMode::Standard.leaf(*hash))
+            .collect();
+        assert_eq!(
+            process_multiproof(&leaves, &fixture.proof, &fixture.proof_flags),
+            Some(fixture.root)
+        );
+    }
+
+    #[test]
+    fn multiproof_round_trips_for_every_subset() {
+        for size in 1..=7u64 {
+            let orders: Vec<Order> = (0..size).map(order).collect();
+            let tree = MerkleTree::from_orders(&orders, MerkleTreeMode::Standard);
+
+            for subset in 0u32..1 << size {
+                let indices: Vec<usize> = (0..size as usize)
+                    .filter(|index| subset & (1 << index) != 0)
+                    .collect();
+                let multiproof = tree.multiproof(&indices).unwrap();
+
+                let mut proven = multiproof.leaf_indices.clone();
+                proven.sort();
+                assert_eq!(proven, indices, "size {size}, subset {subset:b}");
+
+                let leaves: Vec<FixedBytes<32>> = multiproof
+                    .leaf_indices
+                    .iter()
+                    .map(|&index| tree.leaves()[index])
+                    .collect();
+                assert_eq!(
+                    process_multiproof(&leaves, &multiproof.proof, &multiproof.proof_flags),
+                    Some(tree.root()),
+                    "size {size}, subset {subset:b}"
+                );
             }
         }
+    }
+
+    #[test]
+    fn multiproof_rejects_invalid_requests() {
+        let orders: Vec<Order> = (0..5).map(order).collect();
+        let standard = MerkleTree::from_orders(&orders, MerkleTreeMode::Standard);
+        assert!(standard.multiproof(&[1, 1]).is_none());
+        assert!(standard.multiproof(&[5]).is_none());
+        assert!(MerkleTree::from_orders(&orders, MerkleTreeMode::Legacy)
+            .multiproof(&[0])
+            .is_none());
+        assert!(MerkleTree::from_orders(&[], MerkleTreeMode::Standard)
+            .multiproof(&[])
+            .is_none());
 
-        current_index /= 2;
-        hashes = next_level;
+        // a leaf swapped for one outside the proven set must not reproduce the root
+        let multiproof = standard.multiproof(&[0, 2]).unwrap();
+        let leaves: Vec<FixedBytes<32>> = multiproof
+            .leaf_indices
+            .iter()
+            .map(|&index| standard.leaves()[if index == 0 { 4 } else { index }])
+            .collect();
+        assert_ne!(
+            process_multiproof(&leaves, &multiproof.proof, &multiproof.proof_flags),
+            Some(standard.root())
+        );
     }
 
-    proof
+    #[test]
+    fn verify_order_proof_accepts_fixture_proofs() {
+        for (json, mode) in [
+            (STANDARD_5, MerkleTreeMode::Standard),
+            (LEGACY_5, MerkleTreeMode::Legacy),
+        ] {
+            let (data, _) = fixture_orders(json);
+            for proof in &data.proofs {
+                assert!(verify_order_proof(
+                    &proof.order,
+                    &proof.proof,
+                    proof.leaf_index,
+                    data.root,
+                    mode
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn verify_order_proof_rejects_mismatches() {
+        let (data, _) = fixture_orders(STANDARD_5);
+        let proof = &data.proofs[1];
+        let verify = |order: &Order, path: &[FixedBytes<32>], root, mode| {
+            verify_order_proof(order, path, proof.leaf_index, root, mode)
+        };
+        assert!(verify(
+            &proof.order,
+            &proof.proof,
+            data.root,
+            MerkleTreeMode::Standard
+        ));
+
+        let mut tampered = proof.order.clone();
+        tampered.amount += U256::from(1);
+        assert!(!verify(
+            &tampered,
+            &proof.proof,
+            data.root,
+            MerkleTreeMode::Standard
+        ));
+        let mut extended = proof.order.clone();
+        extended.fill_deadline += 1;
+        assert!(!verify(
+            &extended,
+            &proof.proof,
+            data.root,
+            MerkleTreeMode::Standard
+        ));
+
+        assert!(!verify(
+            &proof.order,
+            &proof.proof,
+            FixedBytes::ZERO,
+            MerkleTreeMode::Standard
+        ));
+        assert!(!verify(
+            &proof.order,
+            &proof.proof[1..],
+            data.root,
+            MerkleTreeMode::Standard
+        ));
+        // a standard path does not verify the raw order hash as a leaf
+        assert!(!verify(
+            &proof.order,
+            &proof.proof,
+            data.root,
+            MerkleTreeMode::Legacy
+        ));
+    }
+
+    #[test]
+    fn verify_order_proof_rejects_unaddressable_legacy_index() {
+        let (data, _) = fixture_orders(LEGACY_5);
+        let proof = &data.proofs[0];
+        let depth = proof.proof.len();
+        let verify = |leaf_index| {
+            verify_order_proof(
+                &proof.order,
+                &proof.proof,
+                leaf_index,
+                data.root,
+                MerkleTreeMode::Legacy,
+            )
+        };
+        assert!(verify((1 << depth) - 1));
+        assert!(!verify(1 << depth));
+        assert!(!verify(usize::MAX));
+    }
+
+    #[test]
+    fn verify_nullifier_proof_binds_order_to_fill() {
+        let orders: Vec<Order> = (0..5).map(order).collect();
+        let claims: Vec<ClaimedExecution> = (0..5u8)
+            .map(|i| ClaimedExecution {
+                chain_id: 11155111,
+                tx_hash: keccak256([i]),
+            })
+            .collect();
+
+        for mode in [MerkleTreeMode::Legacy, MerkleTreeMode::Standard] {
+            let data = generate_all_nullifier_proofs(&orders, &claims, mode);
+            assert_eq!(data.root, generate_nullifier_root(&orders, &claims, mode));
+            for (index, proof) in data.proofs.iter().enumerate() {
+                let order_hash = orders[index].hash();
+                assert!(verify_nullifier_proof(
+                    order_hash,
+                    proof.nullifier,
+                    &proof.proof,
+                    data.root,
+                    mode
+                ));
+                // the same fill can't be claimed for another order
+                let other = orders[(index + 1) % orders.len()].hash();
+                assert!(!verify_nullifier_proof(
+                    other,
+                    proof.nullifier,
+                    &proof.proof,
+                    data.root,
+                    mode
+                ));
+            }
+        }
+    }
+
+    /// Root of the depth-[`ACCUMULATOR_DEPTH`] tree over `hashes`, built level by level with
+    /// every missing node zero-padded
+    fn naive_accumulator_root(hashes: &[FixedBytes<32>]) -> FixedBytes<32> {
+        let mut level: Vec<[u8; 32]> = hashes
+            .iter()
+            .map(|hash| MerkleTreeMode::Standard.leaf(*hash).0)
+            .collect();
+        let mut zero = [0u8; 32];
+        for _ in 0..ACCUMULATOR_DEPTH {
+            if level.is_empty() {
+                level.push(zero);
+            }
+            if level.len() % 2 == 1 {
+                level.push(zero);
+            }
+            level = level
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], &pair[1]))
+                .collect();
+            zero = hash_pair(&zero, &zero);
+        }
+        level[0].into()
+    }
+
+    #[test]
+    fn accumulator_matches_naive_tree() {
+        let hashes: Vec<FixedBytes<32>> = (0..40u8).map(|i| keccak256([i])).collect();
+        let mut accumulator = OrderAccumulator::new();
+        assert_eq!(accumulator.root(), naive_accumulator_root(&[]));
+        assert_eq!(accumulator.root(), EMPTY_ACCUMULATOR_ROOT);
+
+        for (count, hash) in hashes.iter().enumerate() {
+            accumulator.append(*hash);
+            assert_eq!(accumulator.count, count as u64 + 1);
+            assert_eq!(
+                accumulator.root(),
+                naive_accumulator_root(&hashes[..=count]),
+                "after {} appends",
+                count + 1
+            );
+        }
+    }
+
+    #[test]
+    fn accumulator_extends_across_batches() {
+        let orders: Vec<Order> = (0..11).map(order).collect();
+        let mut whole = OrderAccumulator::new();
+        whole.extend(&orders);
+
+        // persisted between runs, then extended by the next batch
+        let mut batched = OrderAccumulator::new();
+        batched.extend(&orders[..4]);
+        let json = serde_json::to_string(&batched).unwrap();
+        let mut batched: OrderAccumulator = serde_json::from_str(&json).unwrap();
+        batched.extend(&orders[4..]);
+
+        assert_eq!(batched, whole);
+        let hashes: Vec<FixedBytes<32>> = orders.iter().map(Order::hash).collect();
+        assert_eq!(whole.root(), naive_accumulator_root(&hashes));
+    }
+
+    fn order_on(source_chain_id: u64, i: u64) -> Order {
+        Order {
+            source_chain_id,
+            ..order(i)
+        }
+    }
+
+    #[test]
+    fn fill_reused_across_batches_and_chains_is_rejected() {
+        // the same transfer pays both orders, which settle on different chains
+        let base_order = order_on(84532, 1);
+        let starknet_order = order_on(393402133025997798, 1);
+        let fill_input = base_order.hash();
+
+        // batch 1 settles the fill on Base Sepolia, the only chain that records its nullifier
+        assert!(base_order.is_named_by(fill_input.as_slice()));
+        // batch 2 can't claim it again for the Starknet order
+        assert!(!starknet_order.is_named_by(fill_input.as_slice()));
+
+        // an ERC-20 fill carries the hash after the `transfer` arguments
+        let mut erc20_input = vec![0xa9, 0x05, 0x9c, 0xbb];
+        erc20_input.extend_from_slice(&[0; 64]);
+        erc20_input.extend_from_slice(starknet_order.hash().as_slice());
+        assert!(starknet_order.is_named_by(&erc20_input));
+        assert!(!base_order.is_named_by(&erc20_input));
+        assert!(!base_order.is_named_by(&[]));
+    }
+
+    #[test]
+    fn chain_accumulators_skip_batches_without_orders() {
+        let batches = [
+            vec![order_on(1, 0), order_on(2, 1), order_on(1, 2)],
+            vec![order_on(2, 3)],
+            vec![order_on(2, 4), order_on(1, 5)],
+        ];
+
+        let mut accumulators = ChainAccumulators::new();
+        let mut transitions = Vec::new();
+        for batch in &batches {
+            // persisted between runs
+            let json = serde_json::to_string(&accumulators).unwrap();
+            accumulators = serde_json::from_str(&json).unwrap();
+            transitions.push(accumulators.extend(batch));
+        }
+
+        // chain 1 has no orders in the second batch, so its third transition extends its first
+        let chains: Vec<Vec<u64>> = transitions
+            .iter()
+            .map(|batch| batch.iter().map(|t| t.chain_id).collect())
+            .collect();
+        assert_eq!(chains, [vec![1, 2], vec![2], vec![1, 2]]);
+        assert_eq!(transitions[2][0].previous_root, transitions[0][0].root);
+        assert_eq!(transitions[1][0].previous_root, transitions[0][1].root);
+        assert_eq!(transitions[2][1].previous_root, transitions[1][0].root);
+        assert_eq!(transitions[0][0].previous_root, EMPTY_ACCUMULATOR_ROOT);
+
+        // each chain's accumulator only holds its own orders
+        for chain_id in [1, 2] {
+            let orders: Vec<Order> = batches
+                .iter()
+                .flatten()
+                .filter(|order| order.source_chain_id == chain_id)
+                .cloned()
+                .collect();
+            let mut expected = OrderAccumulator::new();
+            expected.extend(&orders);
+            assert_eq!(accumulators.get(chain_id), expected);
+        }
+        assert_eq!(accumulators.get(3), OrderAccumulator::new());
+    }
+
+    #[test]
+    fn accumulator_transitions_prove_against_their_root() {
+        let mut accumulators = ChainAccumulators::new();
+        let orders: Vec<Order> = (0..6).map(|i| order_on(1 + i % 3, i)).collect();
+        let transitions = accumulators.extend(&orders);
+        assert_eq!(transitions.len(), 3);
+
+        for mode in [MerkleTreeMode::Legacy, MerkleTreeMode::Standard] {
+            let tree = accumulator_transitions_tree(&transitions, mode);
+            for (index, transition) in transitions.iter().enumerate() {
+                let leaf = mode.leaf(transition.hash());
+                assert_eq!(process_proof(leaf, &tree.proof(index)), tree.root());
+
+                // a transition for another chain doesn't verify with this path
+                let other = AccumulatorTransition {
+                    chain_id: transition.chain_id + 10,
+                    ..transition.clone()
+                };
+                let leaf = mode.leaf(other.hash());
+                assert_ne!(process_proof(leaf, &tree.proof(index)), tree.root());
+            }
+        }
+    }
+
+    #[test]
+    fn order_proof_rejects_a_mismatched_order_hash() {
+        let mut order_proof = ArtifactOrderProof {
+            order: order(1),
+            order_hash: order(1).hash(),
+            proof: Vec::new(),
+            leaf_index: 0,
+            nullifier: FixedBytes::ZERO,
+            nullifier_proof: Vec::new(),
+        };
+        assert!(order_proof.check_order_hash().is_ok());
+
+        order_proof.order.amount += U256::from(1);
+        assert!(order_proof.check_order_hash().is_err());
+    }
 }
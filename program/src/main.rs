@@ -8,34 +8,181 @@
 #![no_main]
 sp1_zkvm::entrypoint!(main);
 
-use alloy_consensus::Transaction;
+use std::collections::BTreeSet;
+
+use alloy_consensus::{Transaction, TxReceipt};
 use bankai_types::ProofWrapper;
 use bankai_verify::verify_batch_proof;
-use settlement_lib::{generate_merkle_root, Order};
+use settlement_lib::{
+    accumulator_transitions_tree, batch_destination_chain_id, generate_merkle_root_with_mode,
+    generate_nullifier_root, BankaiNetwork, ChainAccumulators, ClaimedExecution, MerkleTreeMode,
+    Order, SettlementOutput, SETTLEMENT_OUTPUT_VERSION,
+};
 
 pub fn main() {
     // Read an input to the program.
     println!("Entering zkVM...");
     let proof_batch = sp1_zkvm::io::read::<ProofWrapper>();
     let orders = sp1_zkvm::io::read::<Vec<Order>>();
+    let claims = sp1_zkvm::io::read::<Vec<ClaimedExecution>>();
+    let tree_mode = sp1_zkvm::io::read::<MerkleTreeMode>();
+    let mut accumulators = sp1_zkvm::io::read::<ChainAccumulators>();
+    let bankai_network = sp1_zkvm::io::read::<BankaiNetwork>();
     println!("Retrieved Inputs...");
 
     // verify the proof, containing all the claimed executions
     let res = verify_batch_proof(proof_batch).unwrap();
 
-    // iterate throught the orders, asserting they match the veried txs
-    for (index, order) in orders.iter().enumerate() {
+    // every order needs exactly one claimed execution, and every verified tx must be claimed
+    assert_eq!(
+        orders.len(),
+        claims.len(),
+        "Expected one claimed execution per order"
+    );
+    assert_eq!(
+        orders.len(),
+        res.evm.tx.len(),
+        "Expected one verified transaction per order"
+    );
+    assert_eq!(
+        orders.len(),
+        res.evm.receipt.len(),
+        "Expected one verified receipt per order"
+    );
+
+    // all orders in a batch are filled on the same destination chain. The committed Bankai
+    // network is only bound through the fills' signed chain id, so an empty batch is rejected
+    // rather than committing an unchecked network
+    let destination_chain_id =
+        batch_destination_chain_id(&orders).unwrap_or_else(|e| panic!("Invalid batch: {e}"));
+    assert_eq!(
+        destination_chain_id,
+        bankai_network.chain_id(),
+        "Batch destination chain {destination_chain_id} is not the {bankai_network} network"
+    );
+
+    // iterate throught the orders, asserting they match the verified txs
+    let mut used_txs = BTreeSet::new();
+    for (index, (order, claim)) in orders.iter().zip(claims.iter()).enumerate() {
         println!("Verifying Order: {index:?}");
-        let tx = &res.evm.tx[index];
 
-        assert_eq!(tx.to(), Some(order.receiver));
-        assert_eq!(tx.value(), order.amount);
-        assert_eq!(tx.chain_id(), Some(order.destination_chain_id));
+        assert_eq!(
+            claim.chain_id, order.destination_chain_id,
+            "Order {index}: claimed execution is on chain {}, expected {}",
+            claim.chain_id, order.destination_chain_id
+        );
+        assert!(
+            used_txs.insert(claim.tx_hash),
+            "Order {index}: tx {} is already used by another order",
+            claim.tx_hash
+        );
+
+        let tx = res
+            .evm
+            .tx
+            .iter()
+            .find(|tx| *tx.tx_hash() == claim.tx_hash)
+            .unwrap_or_else(|| panic!("Order {index}: tx {} was not verified", claim.tx_hash));
+
+        // fills are signed for a chain id, tying the batch to the network Bankai attests to
+        assert_eq!(
+            tx.chain_id(),
+            Some(order.destination_chain_id),
+            "Order {index}: tx {} has the wrong chain id",
+            claim.tx_hash
+        );
+
+        // a reverted transfer must not count as a fill
+        let receipt = res
+            .evm
+            .receipt
+            .iter()
+            .find(|receipt| receipt.tx_hash == claim.tx_hash)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Order {index}: receipt for tx {} was not verified",
+                    claim.tx_hash
+                )
+            });
+        assert!(
+            receipt.receipt.status(),
+            "Order {index}: tx {} did not succeed",
+            claim.tx_hash
+        );
+
+        // an unrelated older (or later) transfer must not count as a fill
+        let window = order.fill_window();
+        assert!(
+            window.contains(&receipt.block_number),
+            "Order {index}: tx {} is in block {}, outside the fill window {}..={}",
+            claim.tx_hash,
+            receipt.block_number,
+            window.start(),
+            window.end()
+        );
+
+        // the fill names its order, so its nullifier can't settle another order on another
+        // chain in a later batch
+        assert!(
+            order.is_named_by(tx.input()),
+            "Order {index}: tx {} doesn't end its calldata with the order hash",
+            claim.tx_hash
+        );
+
+        if order.is_native() {
+            assert_eq!(
+                tx.to(),
+                Some(order.receiver),
+                "Order {index}: tx {} has the wrong receiver",
+                claim.tx_hash
+            );
+            assert_eq!(
+                tx.value(),
+                order.amount,
+                "Order {index}: tx {} has the wrong value",
+                claim.tx_hash
+            );
+        } else {
+            assert!(
+                order.find_transfer(receipt.receipt.logs()).is_some(),
+                "Order {index}: tx {} has no matching {} Transfer log",
+                claim.tx_hash,
+                order.token
+            );
+        }
     }
     println!("All orders ok! Merkelizing...");
 
-    let root = generate_merkle_root(orders.as_slice());
+    let root = generate_merkle_root_with_mode(orders.as_slice(), tree_mode);
     println!("Verification Root: {root:?}");
 
-    sp1_zkvm::io::commit_slice(root.as_slice());
+    // bind every order to its consumed fill, so the fill can't settle another order later
+    let nullifiers_root = generate_nullifier_root(orders.as_slice(), claims.as_slice(), tree_mode);
+    println!("Nullifiers Root: {nullifiers_root:?}");
+
+    // extend each settlement chain's accumulator with its orders, committing the transitions
+    let transitions = accumulators.extend(orders.as_slice());
+    for transition in &transitions {
+        println!(
+            "Accumulator of chain {}: {:?} -> {:?} ({} orders)",
+            transition.chain_id, transition.previous_root, transition.root, transition.count
+        );
+    }
+    let accumulators_root = accumulator_transitions_tree(&transitions, tree_mode).root();
+
+    // commit the root together with the Ethereum block Bankai attested to
+    let output = SettlementOutput {
+        version: SETTLEMENT_OUTPUT_VERSION,
+        tree_mode: tree_mode as u8,
+        bankai_network: bankai_network as u8,
+        orders_root: root,
+        nullifiers_root,
+        block_number: res.bankai_block.execution.block_number,
+        block_hash: res.bankai_block.execution.block_hash,
+        destination_chain_id,
+        order_count: orders.len() as u64,
+        accumulators_root,
+    };
+
+    sp1_zkvm::io::commit_slice(&output.encode());
 }
@@ -1,9 +1,24 @@
+use alloy_primitives::FixedBytes;
 use clap::{Parser, Subcommand};
+use serde::Serialize;
+use settlement_lib::{
+    generate_merkle_root_with_mode, process_proof, verify_nullifier_proof, verify_order_proof,
+    BankaiNetwork, MerkleTreeMode, ProofSystem, SettlementArtifact, SettlementOutput,
+    BANKAI_VKEY_HASH,
+};
 use settlement_script::client::{
-    Chain, ChainClient, ChainConfig, EvmClient, ProofData, SolanaClient, StarknetClient,
-    Transaction,
+    load_artifact, ChainClient, ChainConfig, ChainFamily, ChainRegistry, ChainReport, ChainStatus,
+    OrderStatus, OutputFormat, Report, SettlementError, Transaction, DEFAULT_CHAINS_FILE,
 };
+use settlement_script::keys::load_or_setup;
+use sp1_sdk::{HashableKey, Prover, ProverClient, SP1ProofWithPublicValues, SP1VerifyingKey};
+use sp1_verifier::{Groth16Verifier, PlonkVerifier, GROTH16_VK_BYTES, PLONK_VK_BYTES};
+use std::collections::BTreeSet;
 use std::fs;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::task::JoinSet;
 
 #[derive(Parser, Debug)]
 #[command(name = "settlement-cli")]
@@ -20,53 +35,87 @@ struct Cli {
     #[arg(short = 't', long, default_value = "txs.json", global = true)]
     txs_file: String,
 
-    /// EVM Private key for signing transactions (from PRIVATE_KEY env var)
-    #[arg(
-        short = 'k',
-        long,
-        env = "PRIVATE_KEY",
-        global = true,
-        hide_env_values = true
-    )]
+    /// Path to the chain registry
+    #[arg(long, env = "CHAINS_FILE", default_value = DEFAULT_CHAINS_FILE, global = true)]
+    chains_file: String,
+
+    /// EVM private key, overriding the signer configured for EVM chains in the registry
+    #[arg(short = 'k', long, global = true)]
     private_key: Option<String>,
 
-    /// Solana keypair path or JSON array (from SOLANA_PRIVATE_KEY env var)
-    #[arg(
-        long,
-        env = "SOLANA_PRIVATE_KEY",
-        global = true,
-        hide_env_values = true
-    )]
+    /// Solana keypair path or JSON array, overriding the signer configured for Solana chains
+    #[arg(long, global = true)]
     solana_private_key: Option<String>,
 
     /// Dry run mode - don't actually send transactions
     #[arg(long, global = true)]
     dry_run: bool,
+
+    /// Directory caching the proving and verifying keys, shared with the proving script
+    #[arg(long, default_value = ".sp1-keys", global = true)]
+    keys_dir: String,
+
+    /// Output format: text, or json for one structured result per chain and order
+    #[arg(long, env = "OUTPUT_FORMAT", default_value = "text", global = true)]
+    output: OutputFormat,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Initialize the Solana program state (only needed once)
     Initialize {
-        /// Chain name (solana-devnet)
+        /// Chain name or alias of a Solana chain (e.g. solana-devnet)
         chain: String,
     },
     /// Reset orders on one or all chains
     Reset {
-        /// Chain name (base-sepolia, arbitrum-sepolia, solana-devnet, starknet-sepolia, all)
+        /// Chain name or alias from the chain registry, or all
         #[arg(default_value = "all")]
         chain: String,
     },
-    /// Settle orders on a specific chain
+    /// Settle orders on a specific chain, or concurrently on every chain in the proof file
     Settle {
-        /// Chain name (base-sepolia, arbitrum-sepolia, solana-devnet, starknet-sepolia)
+        /// Chain name or alias from the chain registry, or all
         chain: String,
+        /// With all: cancel the remaining chains as soon as one fails
+        #[arg(long)]
+        fail_fast: bool,
     },
-    /// Submit orders from txs.json to a specific destination chain
+    /// Submit orders from txs.json to a specific source chain, or concurrently to all of them
     Submit {
-        /// Chain name (base-sepolia, arbitrum-sepolia, solana-devnet, starknet-sepolia)
+        /// Chain name or alias from the chain registry, or all
+        chain: String,
+        /// With all: cancel the remaining chains as soon as one fails
+        #[arg(long)]
+        fail_fast: bool,
+    },
+    /// Verify the proof file's merkle and nullifier proofs offline, without sending anything
+    Check {
+        /// Only check the entry for this order hash
+        #[arg(long)]
+        order_hash: Option<String>,
+    },
+    /// Verify the SP1 proof and its public values offline against the settlement program
+    Verify {
+        /// Accept a mock proof and only check its public values and order proofs
+        #[arg(long)]
+        allow_mock: bool,
+    },
+    /// Compare the vkey stored by deployed contracts with the local settlement program's
+    CheckVkey {
+        /// Chain name or alias from the chain registry, or all
+        #[arg(default_value = "all")]
         chain: String,
     },
+    /// Report whether each order in the proof file (or txs.json) is submitted or settled
+    Status {
+        /// Chain name or alias from the chain registry, or all
+        #[arg(default_value = "all")]
+        chain: String,
+        /// Read the orders from txs.json instead of the proof file
+        #[arg(long)]
+        from_txs: bool,
+    },
 }
 
 #[tokio::main]
@@ -74,92 +123,691 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv::dotenv().ok();
     let cli = Cli::parse();
 
-    let proof_json = fs::read_to_string(&cli.proof_file)?;
-    let proof_data: ProofData = serde_json::from_str(&proof_json)?;
+    // The offline checks only print progress lines, so refuse rather than mix them into JSON
+    if cli.output == OutputFormat::Json {
+        let command = match cli.command {
+            Commands::Check { .. } => Some("check"),
+            Commands::Verify { .. } => Some("verify"),
+            Commands::CheckVkey { .. } => Some("check-vkey"),
+            _ => None,
+        };
+        if let Some(command) = command {
+            return Err(format!("{command} doesn't support --output json").into());
+        }
+    }
+
+    // Only the commands working on a proof need the proof file
+    let load_proof = || load_artifact(&cli.proof_file);
+    let load_registry = || ChainRegistry::load(&cli.chains_file);
+    let dry_run = cli.dry_run;
 
     match &cli.command {
         Commands::Initialize { chain } => {
-            let chain = Chain::from_name(chain)?;
-            if !chain.is_solana() {
+            let registry = load_registry()?;
+            let chain = registry.find(chain)?;
+            if chain.family != ChainFamily::Solana {
                 return Err("Initialize command is only supported for Solana chains".into());
             }
-            let client = create_client(chain, &cli)?;
-            client.initialize(cli.dry_run).await?;
+            let report = run_on_chain(chain, &cli, |client, mut report| async move {
+                let result = client.initialize(dry_run, &mut report).await;
+                report.finish(result)
+            })
+            .await;
+            finish_chain(report, &cli)?;
         }
         Commands::Reset { chain } => {
-            if chain.to_lowercase() == "all" {
-                println!("🔄 Resetting orders on all chains...\n");
-                for chain in [
-                    Chain::BaseSepolia,
-                    Chain::ArbitrumSepolia,
-                    Chain::SolanaDevnet,
-                    Chain::StarknetSepolia,
-                ] {
-                    match create_client(chain, &cli) {
+            let proof_data = load_proof()?;
+            let registry = load_registry()?;
+            let reset = |client: Box<dyn ChainClient>, mut report: Report| {
+                let proof_data = &proof_data;
+                async move {
+                    let result = client.reset_orders(proof_data, dry_run, &mut report).await;
+                    report.finish(result)
+                }
+            };
+            if chain.eq_ignore_ascii_case("all") {
+                cli.output.print("🔄 Resetting orders on all chains...\n");
+                let mut reports = Vec::new();
+                for chain in &registry.chains {
+                    let report = match create_client(chain, &cli) {
                         Ok(client) => {
-                            if let Err(e) = client.reset_orders(&proof_data, cli.dry_run).await {
+                            let report = reset(client, Report::new(cli.output, chain, dry_run));
+                            let report = report.await;
+                            if let Some(e) = &report.error {
                                 eprintln!("Error resetting orders: {e}");
                             }
-                            println!();
+                            report
                         }
                         Err(e) => {
-                            println!("⚠️  Skipping chain: {e}");
-                            println!();
+                            cli.output.print(format!("⚠️  Skipping chain: {e}"));
+                            ChainReport::failed(chain, dry_run, SettlementError::config(e))
                         }
-                    }
+                    };
+                    cli.output.print("");
+                    reports.push(report);
                 }
                 if cli.dry_run {
-                    println!("🔍 Dry run completed - no transactions sent");
+                    cli.output
+                        .print("🔍 Dry run completed - no transactions sent");
                 } else {
-                    println!("✅ All reset operations completed");
+                    cli.output.print("✅ All reset operations completed");
                 }
+                print_reports(&reports, &cli)?;
             } else {
-                let chain = Chain::from_name(chain)?;
-                let client = create_client(chain, &cli)?;
-                client.reset_orders(&proof_data, cli.dry_run).await?;
+                let report = run_on_chain(registry.find(chain)?, &cli, reset).await;
+                finish_chain(report, &cli)?;
             }
         }
-        Commands::Settle { chain } => {
-            let chain = Chain::from_name(chain)?;
-            let client = create_client(chain, &cli)?;
-            client.settle_orders(&proof_data, cli.dry_run).await?;
+        Commands::Settle { chain, fail_fast } => {
+            let proof_data = load_proof()?;
+            if proof_data.mock && !cli.dry_run {
+                return Err(
+                    "proof file holds a mock proof, which can't be verified on-chain; use --dry-run"
+                        .into(),
+                );
+            }
+            let registry = load_registry()?;
+            if chain.eq_ignore_ascii_case("all") {
+                let source_chains = proof_data.proofs_by_source_chain.keys().copied();
+                let targets = ChainTargets::new(&registry, source_chains);
+                cli.output
+                    .print(format!("⚡ Settling on {}...\n", targets.describe()));
+
+                let proof_data = Arc::new(proof_data);
+                run_on_chains(
+                    "Settlement",
+                    targets,
+                    &cli,
+                    *fail_fast,
+                    |client, mut report| {
+                        let proof_data = proof_data.clone();
+                        async move {
+                            let result = client
+                                .settle_orders(&proof_data, dry_run, &mut report)
+                                .await;
+                            report.finish(result)
+                        }
+                    },
+                )
+                .await?;
+            } else {
+                let report = run_on_chain(registry.find(chain)?, &cli, |client, mut report| {
+                    let proof_data = &proof_data;
+                    async move {
+                        let result = client.settle_orders(proof_data, dry_run, &mut report).await;
+                        report.finish(result)
+                    }
+                })
+                .await;
+                finish_chain(report, &cli)?;
+            }
         }
-        Commands::Submit { chain } => {
-            let chain = Chain::from_name(chain)?;
+        Commands::Submit { chain, fail_fast } => {
+            let registry = load_registry()?;
             let txs_json = fs::read_to_string(&cli.txs_file)?;
             let transactions: Vec<Transaction> = serde_json::from_str(&txs_json)?;
-            let client = create_client(chain, &cli)?;
-            client.submit_orders(&transactions, cli.dry_run).await?;
+            if chain.eq_ignore_ascii_case("all") {
+                let source_chains = transactions.iter().map(|tx| tx.source_chain_id);
+                let targets = ChainTargets::new(&registry, source_chains);
+                cli.output
+                    .print(format!("📝 Submitting to {}...\n", targets.describe()));
+
+                let transactions = Arc::new(transactions);
+                run_on_chains(
+                    "Submission",
+                    targets,
+                    &cli,
+                    *fail_fast,
+                    |client, mut report| {
+                        let transactions = transactions.clone();
+                        async move {
+                            let result = client
+                                .submit_orders(&transactions, dry_run, &mut report)
+                                .await;
+                            report.finish(result)
+                        }
+                    },
+                )
+                .await?;
+            } else {
+                let report = run_on_chain(registry.find(chain)?, &cli, |client, mut report| {
+                    let transactions = &transactions;
+                    async move {
+                        let result = client
+                            .submit_orders(transactions, dry_run, &mut report)
+                            .await;
+                        report.finish(result)
+                    }
+                })
+                .await;
+                finish_chain(report, &cli)?;
+            }
+        }
+        Commands::Check { order_hash } => {
+            check_proofs(&load_proof()?, order_hash.as_deref())?;
+        }
+        Commands::Verify { allow_mock } => {
+            verify_proof_file(&load_proof()?, &cli.keys_dir, *allow_mock)?;
+        }
+        Commands::CheckVkey { chain } => {
+            let registry = load_registry()?;
+            check_vkeys(&registry.select(chain)?, &cli).await?;
+        }
+        Commands::Status { chain, from_txs } => {
+            let orders: Vec<(u64, FixedBytes<32>)> = if *from_txs {
+                let txs_json = fs::read_to_string(&cli.txs_file)?;
+                let transactions: Vec<Transaction> = serde_json::from_str(&txs_json)?;
+                transactions
+                    .iter()
+                    .map(|tx| Ok((tx.source_chain_id, tx.order()?.hash())))
+                    .collect::<Result<_, Box<dyn std::error::Error>>>()?
+            } else {
+                load_proof()?
+                    .proofs_by_source_chain
+                    .iter()
+                    .flat_map(|(chain_id, proofs)| {
+                        proofs.iter().map(move |op| (*chain_id, op.order_hash))
+                    })
+                    .collect()
+            };
+            let registry = load_registry()?;
+            let chains = if chain.eq_ignore_ascii_case("all") {
+                let targets = ChainTargets::new(&registry, orders.iter().map(|(id, _)| *id));
+                for chain_id in &targets.unknown {
+                    cli.output.print(format!(
+                        "⚠️  Source chain {chain_id} has no entry in the chain registry"
+                    ));
+                }
+                targets.chains
+            } else {
+                vec![registry.find(chain)?]
+            };
+            order_statuses(&chains, &orders, &cli).await?;
         }
     }
 
     Ok(())
 }
 
+/// The registry chains a multi-chain run fans out to, plus source chain ids without an entry
+struct ChainTargets<'a> {
+    chains: Vec<&'a ChainConfig>,
+    unknown: Vec<u64>,
+}
+
+impl<'a> ChainTargets<'a> {
+    fn new(registry: &'a ChainRegistry, source_chains: impl IntoIterator<Item = u64>) -> Self {
+        let source_chains: BTreeSet<u64> = source_chains.into_iter().collect();
+        let chains: Vec<&ChainConfig> = registry
+            .chains
+            .iter()
+            .filter(|chain| source_chains.contains(&chain.chain_id))
+            .collect();
+        let unknown = source_chains
+            .into_iter()
+            .filter(|chain_id| !chains.iter().any(|chain| chain.chain_id == *chain_id))
+            .collect();
+        Self { chains, unknown }
+    }
+
+    fn describe(&self) -> String {
+        let names: Vec<&str> = self
+            .chains
+            .iter()
+            .map(|chain| chain.display_name.as_str())
+            .collect();
+        format!("{} chains: {}", names.len(), names.join(", "))
+    }
+}
+
+/// Runs a command on one chain, reporting a client that can't be created as a failed chain
+async fn run_on_chain<F, Fut>(chain: &ChainConfig, cli: &Cli, run: F) -> ChainReport
+where
+    F: FnOnce(Box<dyn ChainClient>, Report) -> Fut,
+    Fut: Future<Output = ChainReport>,
+{
+    match create_client(chain, cli) {
+        Ok(client) => run(client, Report::new(cli.output, chain, cli.dry_run)).await,
+        Err(e) => ChainReport::failed(chain, cli.dry_run, SettlementError::config(e)),
+    }
+}
+
+/// Prints the chain reports as one JSON document, if that's the output format
+fn print_reports(reports: &[ChainReport], cli: &Cli) -> Result<(), serde_json::Error> {
+    if cli.output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(reports)?);
+    }
+    Ok(())
+}
+
+/// Prints a single-chain command's report and fails with the chain's error, if any
+fn finish_chain(report: ChainReport, cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+    print_reports(std::slice::from_ref(&report), cli)?;
+    match report.error {
+        Some(error) => Err(error.into()),
+        None => Ok(()),
+    }
+}
+
+/// Runs `run` on every target chain concurrently, prints a per-chain summary and fails if any
+/// chain failed. With `fail_fast`, the first failure cancels the chains still running.
+async fn run_on_chains<F, Fut>(
+    action: &str,
+    targets: ChainTargets<'_>,
+    cli: &Cli,
+    fail_fast: bool,
+    run: F,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: Fn(Box<dyn ChainClient>, Report) -> Fut,
+    Fut: Future<Output = ChainReport> + Send + 'static,
+{
+    let mut reports: Vec<ChainReport> = targets
+        .unknown
+        .iter()
+        .map(|chain_id| ChainReport::unregistered(*chain_id, cli.dry_run))
+        .collect();
+
+    let mut clients = Vec::new();
+    for chain in &targets.chains {
+        match create_client(chain, cli) {
+            Ok(client) => {
+                let report = Report::new(cli.output, chain, cli.dry_run);
+                clients.push((reports.len(), client, report));
+                // Replaced once the chain finishes, so it stays cancelled if it never does
+                reports.push(ChainReport::cancelled(chain, cli.dry_run));
+            }
+            Err(e) => {
+                let error = SettlementError::config(e);
+                reports.push(ChainReport::failed(chain, cli.dry_run, error));
+            }
+        }
+    }
+
+    let failed_early = reports
+        .iter()
+        .any(|report| report.status == ChainStatus::Failed);
+    let mut tasks = JoinSet::new();
+    if !(fail_fast && failed_early) {
+        for (index, client, report) in clients {
+            let task = run(client, report);
+            tasks.spawn(async move { (index, task.await) });
+        }
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, report)) => {
+                if report.status == ChainStatus::Failed && fail_fast {
+                    tasks.abort_all();
+                }
+                reports[index] = report;
+            }
+            Err(e) if e.is_cancelled() => {}
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    cli.output.print(format!("\n📊 {action} summary:"));
+    for report in &reports {
+        let name = &report.display_name;
+        match (report.status, &report.error) {
+            (ChainStatus::Succeeded, _) => cli.output.print(format!("   ✅ {name}")),
+            (ChainStatus::Cancelled, _) => cli.output.print(format!("   ⏹️  {name}: cancelled")),
+            (ChainStatus::Failed, error) => {
+                let error = error.as_ref().map(ToString::to_string).unwrap_or_default();
+                cli.output.print(format!("   ❌ {name}: {error}"));
+            }
+        }
+    }
+    print_reports(&reports, cli)?;
+
+    let failed = reports
+        .iter()
+        .filter(|report| report.status != ChainStatus::Succeeded)
+        .count();
+    if failed > 0 {
+        return Err(format!("{failed} of {} chains failed", reports.len()).into());
+    }
+    if cli.dry_run {
+        cli.output
+            .print("\n🔍 Dry run completed - no transactions sent");
+    }
+    Ok(())
+}
+
+fn check_proofs(
+    proof_data: &SettlementArtifact,
+    order_hash: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let order_hash = order_hash.map(FixedBytes::<32>::from_str).transpose()?;
+    let output = proof_data.settlement_output()?;
+    let mode = MerkleTreeMode::try_from(output.tree_mode)?;
+    println!(
+        "🔍 Checking proofs against root {} ({:?} tree)\n",
+        output.orders_root, mode
+    );
+
+    let status = |ok: bool| if ok { "ok" } else { "invalid" };
+
+    let mut checked = 0;
+    let mut failed = 0;
+    for (chain_id, entries) in &proof_data.proofs_by_source_chain {
+        for entry in entries {
+            if order_hash.is_some_and(|hash| hash != entry.order_hash) {
+                continue;
+            }
+            checked += 1;
+
+            let hash = entry.order.hash();
+            let hash_ok = hash == entry.order_hash;
+            let order_ok = verify_order_proof(
+                &entry.order,
+                &entry.proof,
+                entry.leaf_index,
+                output.orders_root,
+                mode,
+            );
+            let nullifier_ok = verify_nullifier_proof(
+                hash,
+                entry.nullifier,
+                &entry.nullifier_proof,
+                output.nullifiers_root,
+                mode,
+            );
+
+            if hash_ok && order_ok && nullifier_ok {
+                println!(
+                    "   ✅ {} (chain {chain_id}, leaf {})",
+                    entry.order_hash, entry.leaf_index
+                );
+            } else {
+                failed += 1;
+                println!(
+                    "   ❌ {} (chain {chain_id}, leaf {}): order hash {}, merkle proof {}, nullifier proof {}",
+                    entry.order_hash,
+                    entry.leaf_index,
+                    status(hash_ok),
+                    status(order_ok),
+                    status(nullifier_ok)
+                );
+            }
+        }
+    }
+
+    if checked == 0 {
+        return Err("No matching orders found in the proof file".into());
+    }
+    if failed > 0 {
+        return Err(format!("{failed} of {checked} proofs failed verification").into());
+    }
+    println!("\n✅ All {checked} proofs verified");
+    Ok(())
+}
+
+/// Computes the settlement program's vkey, reusing the cached setup keys
+fn local_vkey(
+    keys_dir: &str,
+) -> Result<(SP1VerifyingKey, FixedBytes<32>), Box<dyn std::error::Error>> {
+    let client = ProverClient::builder().cpu().build();
+    let (_, vk) = load_or_setup(keys_dir, |elf| client.setup(elf))?;
+    let vkey = FixedBytes::from_str(&vk.bytes32())?;
+    Ok((vk, vkey))
+}
+
+async fn check_vkeys(chains: &[&ChainConfig], cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let (_, vkey) = local_vkey(&cli.keys_dir)?;
+    println!("🔑 Local program vkey: {vkey}\n");
+
+    let mut drifted = 0;
+    for chain in chains {
+        // The Solana program verifies against its hardcoded constant, not the stored state
+        if chain.family == ChainFamily::Solana {
+            let hardcoded = FixedBytes::<32>::from_str(BANKAI_VKEY_HASH)?;
+            if hardcoded == vkey {
+                println!("   ✅ {} BANKAI_VKEY_HASH", chain.display_name);
+            } else {
+                drifted += 1;
+                println!("   ❌ {} BANKAI_VKEY_HASH: {hardcoded}", chain.display_name);
+            }
+        }
+
+        let onchain = match chain.read_only_client() {
+            Ok(client) => client.program_vkey().await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match onchain {
+            Ok(onchain) if onchain == vkey => println!("   ✅ {}", chain.display_name),
+            Ok(onchain) => {
+                drifted += 1;
+                println!("   ❌ {}: deployed vkey {onchain}", chain.display_name);
+            }
+            Err(e) => {
+                drifted += 1;
+                println!(
+                    "   ❌ {}: failed to read the deployed vkey: {e}",
+                    chain.display_name
+                );
+            }
+        }
+    }
+
+    if drifted > 0 {
+        return Err(format!(
+            "{drifted} vkeys don't match the local settlement program or couldn't be read"
+        )
+        .into());
+    }
+    println!("\n✅ All vkeys match");
+    Ok(())
+}
+
+/// One order's status on its source chain, as printed by `status`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusRow {
+    chain: String,
+    chain_id: u64,
+    order_hash: FixedBytes<32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<OrderStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<SettlementError>,
+}
+
+/// Looks up every order on its source chain and prints the result as a table or JSON
+async fn order_statuses(
+    chains: &[&ChainConfig],
+    orders: &[(u64, FixedBytes<32>)],
+    cli: &Cli,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut rows = Vec::new();
+    for chain in chains {
+        let order_hashes = orders
+            .iter()
+            .filter(|(chain_id, _)| *chain_id == chain.chain_id)
+            .map(|(_, order_hash)| *order_hash);
+
+        let client = chain.read_only_client().map_err(SettlementError::config);
+        for order_hash in order_hashes {
+            let result = match &client {
+                Ok(client) => client.order_status(order_hash).await,
+                Err(e) => Err(e.clone()),
+            };
+            let (status, error) = match result {
+                Ok(status) => (Some(status), None),
+                Err(e) => (None, Some(e)),
+            };
+            rows.push(StatusRow {
+                chain: chain.name.clone(),
+                chain_id: chain.chain_id,
+                order_hash,
+                status,
+                error,
+            });
+        }
+    }
+
+    if cli.output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+    } else if rows.is_empty() {
+        println!("⏭️  No orders on the selected chains");
+    } else {
+        let width = rows.iter().map(|row| row.chain.len()).max().unwrap_or(0);
+        println!("{:<width$}  {:<66}  Status", "Chain", "Order hash");
+        for row in &rows {
+            let status = match (&row.status, &row.error) {
+                (Some(OrderStatus::Settled), _) => "✅ settled".to_string(),
+                (Some(OrderStatus::Submitted), _) => "📝 submitted".to_string(),
+                (Some(OrderStatus::Unknown), _) => "❔ unknown".to_string(),
+                (None, error) => {
+                    let error = error.as_ref().map(ToString::to_string).unwrap_or_default();
+                    format!("❌ {error}")
+                }
+            };
+            println!("{:<width$}  {}  {status}", row.chain, row.order_hash);
+        }
+    }
+
+    let failed = rows.iter().filter(|row| row.error.is_some()).count();
+    if failed > 0 {
+        return Err(format!("failed to read the status of {failed} orders").into());
+    }
+    Ok(())
+}
+
+fn verify_proof_file(
+    proof_data: &SettlementArtifact,
+    keys_dir: &str,
+    allow_mock: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!(
+        "🔍 Verifying {} proof offline...\n",
+        proof_data.proof_system
+    );
+    let mut failures = Vec::new();
+
+    let (vk, vkey) = local_vkey(keys_dir)?;
+    let vkey_hash = vkey.to_string();
+    if vkey != proof_data.vkey {
+        failures.push(format!(
+            "proof file vkey {} is not the settlement program's vkey {vkey}",
+            proof_data.vkey
+        ));
+    }
+
+    let public_values = &proof_data.public_values;
+    if proof_data.mock && allow_mock {
+        println!("⚠️  Mock proof, skipping SP1 verification");
+    } else if proof_data.mock {
+        failures
+            .push("mock proof, not verifiable; pass --allow-mock to check the rest".to_string());
+    } else {
+        let proof = &proof_data.proof;
+        let verified = match proof_data.proof_system {
+            ProofSystem::Groth16 => {
+                Groth16Verifier::verify(proof, public_values, &vkey_hash, *GROTH16_VK_BYTES)
+                    .map_err(|e| e.to_string())
+            }
+            ProofSystem::Plonk => {
+                PlonkVerifier::verify(proof, public_values, &vkey_hash, *PLONK_VK_BYTES)
+                    .map_err(|e| e.to_string())
+            }
+            ProofSystem::Compressed | ProofSystem::Core => {
+                let path = proof_data
+                    .proof_file
+                    .as_deref()
+                    .ok_or("proof file has no proofFile for a STARK proof")?;
+                let proof = SP1ProofWithPublicValues::load(path)?;
+                if public_values[..] != *proof.public_values.as_slice() {
+                    failures.push(format!("{path} commits different public values"));
+                }
+                let client = ProverClient::builder().cpu().build();
+                client.verify(&proof, &vk).map_err(|e| e.to_string())
+            }
+        };
+        match verified {
+            Ok(()) => println!("   ✅ SP1 proof verified against vkey {vkey}"),
+            Err(e) => failures.push(format!("SP1 proof verification failed: {e}")),
+        }
+    }
+
+    let output = SettlementOutput::decode(public_values)?;
+    let mode = MerkleTreeMode::try_from(output.tree_mode)?;
+    println!("\n📋 Public values (version {}):", output.version);
+    println!("   Orders root: {} ({mode:?} tree)", output.orders_root);
+    println!("   Nullifiers root: {}", output.nullifiers_root);
+    let network = BankaiNetwork::try_from(output.bankai_network)?;
+    println!(
+        "   Bankai block: {} ({}) on {network}",
+        output.block_number, output.block_hash
+    );
+    println!("   Orders: {}", output.order_count);
+    println!("   Accumulators root: {}", output.accumulators_root);
+    for (chain_id, accumulator) in &proof_data.accumulators {
+        let transition = &accumulator.transition;
+        println!(
+            "   Accumulator of chain {chain_id}: {} -> {} ({} orders)",
+            transition.previous_root, transition.root, transition.count
+        );
+    }
+    println!();
+    if let Err(e) = proof_data.settlement_output() {
+        failures.push(e);
+    }
+
+    let mut orders = Vec::new();
+    for (chain_id, entries) in &proof_data.proofs_by_source_chain {
+        let mut mismatched = 0;
+        for entry in entries {
+            if process_proof(mode.leaf(entry.order.hash()), &entry.proof) != output.orders_root {
+                mismatched += 1;
+            }
+            orders.push((entry.leaf_index, entry.order.clone()));
+        }
+        if mismatched == 0 {
+            println!("   ✅ chain {chain_id}: {} orders", entries.len());
+        } else {
+            println!(
+                "   ❌ chain {chain_id}: {mismatched} of {} orders recompute a different root",
+                entries.len()
+            );
+            failures.push(format!("chain {chain_id}: {mismatched} root mismatches"));
+        }
+    }
+
+    // The embedded orders must also make up the whole committed tree, not just a subset of it
+    orders.sort_by_key(|(leaf_index, _)| *leaf_index);
+    let orders: Vec<_> = orders.into_iter().map(|(_, order)| order).collect();
+    if orders.len() as u64 != output.order_count {
+        failures.push(format!(
+            "proof file holds {} orders, but the public values commit {}",
+            orders.len(),
+            output.order_count
+        ));
+    } else if generate_merkle_root_with_mode(&orders, mode) != output.orders_root {
+        failures.push("the embedded orders don't rebuild the committed root".to_string());
+    }
+
+    if !failures.is_empty() {
+        println!();
+        for failure in &failures {
+            println!("   ❌ {failure}");
+        }
+        return Err(format!("{} verification checks failed", failures.len()).into());
+    }
+    println!("\n✅ Proof file verified");
+    Ok(())
+}
+
 fn create_client(
-    chain: Chain,
+    chain: &ChainConfig,
     cli: &Cli,
 ) -> Result<Box<dyn ChainClient>, Box<dyn std::error::Error>> {
-    let config = ChainConfig::load(chain.clone())?;
-
-    match chain {
-        Chain::BaseSepolia | Chain::ArbitrumSepolia => {
-            let private_key = cli
-                .private_key
-                .as_ref()
-                .ok_or("PRIVATE_KEY must be set for EVM chains")?;
-            Ok(Box::new(EvmClient::new(config, private_key.clone())?))
-        }
-        Chain::SolanaDevnet => {
-            let solana_private_key = cli
-                .solana_private_key
-                .as_ref()
-                .ok_or("SOLANA_PRIVATE_KEY must be set for Solana chains")?;
-            Ok(Box::new(SolanaClient::new(
-                config,
-                solana_private_key.clone(),
-            )?))
-        }
-        Chain::StarknetSepolia => Ok(Box::new(StarknetClient::new(config)?)),
-    }
+    let signer_key = match chain.family {
+        ChainFamily::Evm => cli.private_key.clone(),
+        ChainFamily::Solana => cli.solana_private_key.clone(),
+        ChainFamily::Starknet => None,
+    };
+    chain.client(signer_key)
 }
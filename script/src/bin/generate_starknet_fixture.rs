@@ -1,50 +1,34 @@
-use garaga_rs::calldata::full_proof_with_hints::groth16::{
-    get_groth16_calldata_felt, get_sp1_vk, Groth16Proof,
-};
-use garaga_rs::definitions::CurveID;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
+use settlement_script::client::load_artifact;
+use settlement_script::client::starknet_client::groth16_calldata;
 use std::fs;
 
-#[derive(Debug, Deserialize)]
-struct ProofData {
-    proof: String,
-    #[serde(rename = "publicValues")]
-    public_values: String,
-    vkey: String,
-    #[serde(rename = "merkleRoot")]
-    merkle_root: String,
-}
-
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🔄 Generating Starknet proof fixture...\n");
 
-    let proof_json = fs::read_to_string("proof.json")?;
-    let proof_data: ProofData = serde_json::from_str(&proof_json)?;
+    let artifact = load_artifact("proof.json")?;
+    let output = artifact.settlement_output()?;
 
     println!("📋 Proof Information:");
-    println!("  VKey: {}", proof_data.vkey);
-    println!("  Merkle Root: {}", proof_data.merkle_root);
+    println!("  VKey: {}", artifact.vkey);
+    println!("  Merkle Root: {}", artifact.merkle_root);
+    println!("  Output Root: {}", output.orders_root);
+    println!("  Bankai Block: {}", output.block_number);
     println!();
 
-    let sp1_groth16_vk = get_sp1_vk();
-
-    let vkey_bytes: Vec<u8> = hex::decode(proof_data.vkey.trim_start_matches("0x"))?;
-    let public_values_bytes: Vec<u8> =
-        hex::decode(proof_data.public_values.trim_start_matches("0x"))?;
-    let proof_bytes: Vec<u8> = hex::decode(proof_data.proof.trim_start_matches("0x"))?;
-
-    println!("🔧 Preprocessing with Garaga...");
-    let groth16_proof = Groth16Proof::from_sp1(vkey_bytes, public_values_bytes, proof_bytes);
-
-    let calldata = get_groth16_calldata_felt(&groth16_proof, &sp1_groth16_vk, CurveID::BN254)?;
+    // Garaga calldata is the same for every Starknet chain
+    let calldata = match artifact.calldata.values().next() {
+        Some(calldata) => calldata.clone(),
+        None => {
+            println!("🔧 Preprocessing with Garaga...");
+            groth16_calldata(&artifact)?
+        }
+    };
 
     println!("✅ Generated {} calldata elements", calldata.len());
     println!();
 
-    let calldata_hex: Vec<String> = calldata
-        .iter()
-        .map(|felt| format!("0x{:064x}", felt.to_biguint()))
-        .collect();
+    let calldata_hex: Vec<String> = calldata.iter().map(|word| word.to_string()).collect();
 
     #[derive(Serialize)]
     struct Fixture {
@@ -55,8 +39,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let fixture = Fixture {
-        vkey: proof_data.vkey,
-        merkle_root: proof_data.merkle_root,
+        vkey: artifact.vkey.to_string(),
+        merkle_root: artifact.merkle_root.to_string(),
         proof_calldata: calldata_hex,
         proof_calldata_length: calldata.len(),
     };
@@ -9,22 +9,37 @@
 //! ```shell
 //! RUST_LOG=info cargo run --release -- --prove
 //! ```
+//! Proofs are generated on the Succinct Prover Network by default; pass `--prover cpu` to prove
+//! locally, or `--prover mock` for an unverifiable mock proof that runs fully offline.
+//! Network proof requests are saved under `proof-requests/` when submitted, so an interrupted run
+//! can be finished with `--resume <request-id>`.
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use alloy_primitives::hex::FromHex;
-use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_primitives::FixedBytes;
 use bankai_sdk::{Bankai, HashingFunctionDto, Network};
-use clap::Parser;
+use bankai_types::ProofWrapper;
+use clap::{Parser, ValueEnum};
 use serde::{Deserialize, Serialize};
-use settlement_lib::{generate_all_proofs, ClaimedExecution, Order};
-use sp1_sdk::HashableKey;
+use settlement_lib::{
+    accumulator_transitions_tree, batch_destination_chain_id, generate_all_nullifier_proofs,
+    generate_all_proofs_with_mode, AccumulatorProof, ArtifactOrderProof, BankaiNetwork,
+    ChainAccumulators, ClaimedExecution, MerkleTreeMode, Order, ProofSystem, SettlementArtifact,
+    SettlementOutput, BANKAI_VKEY_HASH, SETTLEMENT_ARTIFACT_VERSION,
+};
+use settlement_script::client::starknet_client::groth16_calldata;
+use settlement_script::client::{ChainFamily, ChainRegistry, Transaction, DEFAULT_CHAINS_FILE};
+use settlement_script::keys::{load_or_setup, VkeyArtifact};
+use settlement_script::SETTLEMENT_ELF;
 use sp1_sdk::Prover;
-use sp1_sdk::{include_elf, network::NetworkMode, ProverClient, SP1Stdin};
-/// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
-pub const SETTLEMENT_ELF: &[u8] = include_elf!("settlement-program");
+use sp1_sdk::{
+    network::NetworkMode, CpuProver, ExecutionReport, NetworkProver, ProverClient,
+    SP1ProofWithPublicValues, SP1ProvingKey, SP1PublicValues, SP1Stdin, SP1VerifyingKey,
+};
 
 /// The arguments for the command.
 #[derive(Parser, Debug)]
@@ -36,19 +51,240 @@ struct Args {
     #[arg(long)]
     prove: bool,
 
+    /// Wait for a network proof request submitted by an earlier `--prove` run and write its
+    /// proof.json
+    #[arg(long, value_name = "REQUEST_ID", conflicts_with_all = ["execute", "prove"])]
+    resume: Option<FixedBytes<32>>,
+
+    /// Directory holding the inputs of submitted network proof requests
+    #[arg(long, default_value = "proof-requests")]
+    requests_dir: String,
+
     /// Path to the transactions JSON file
     #[arg(long, default_value = "txs.json")]
     txs_file: String,
+
+    /// Where the Bankai batch result and orders of each run are saved
+    #[arg(long, default_value = "inputs.bin")]
+    inputs_file: String,
+
+    /// Replay the batch saved in the inputs file instead of fetching it from Bankai
+    #[arg(long)]
+    from_inputs: bool,
+
+    /// Bankai network the fills are proven on: sepolia or mainnet. Committed into the public
+    /// values, so the contracts reject proofs for another network
+    #[arg(long, env = "BANKAI_NETWORK", default_value = "sepolia")]
+    bankai_network: BankaiNetwork,
+
+    /// Hashing function of the Bankai MMR the batch proofs are generated against
+    #[arg(
+        long,
+        env = "BANKAI_HASHING_FUNCTION",
+        value_enum,
+        default_value_t = HashingFunction::Keccak
+    )]
+    hashing_function: HashingFunction,
+
+    /// Merkle tree layout: standard (OpenZeppelin StandardMerkleTree) or legacy
+    #[arg(long, default_value = "standard")]
+    tree_mode: MerkleTreeMode,
+
+    /// Path to the persisted accumulators of all previously proven orders, one per settlement
+    /// chain
+    #[arg(long, default_value = "accumulator.json")]
+    accumulator_file: String,
+
+    /// Where to generate the proof
+    #[arg(long, value_enum, default_value_t = ProverKind::Network)]
+    prover: ProverKind,
+
+    /// SP1 proof type: groth16 or plonk for on-chain settlement, compressed or core for
+    /// off-chain verification only
+    #[arg(long, default_value = "groth16")]
+    proof_system: ProofSystem,
+
+    /// Chain registry, used to check every settlement chain in the batch can verify the proof
+    /// and to key precomputed proof calldata by settlement chain
+    #[arg(long, env = "CHAINS_FILE", default_value = DEFAULT_CHAINS_FILE)]
+    chains_file: String,
+
+    /// Directory caching the proving and verifying keys, keyed by the ELF hash
+    #[arg(long, default_value = ".sp1-keys")]
+    keys_dir: String,
+
+    /// Prove even if a settlement chain in the batch verifies against a different vkey, e.g.
+    /// before redeploying its contract
+    #[arg(long)]
+    allow_vkey_drift: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProverKind {
+    /// Succinct Prover Network (requires NETWORK_PRIVATE_KEY)
+    Network,
+    /// Local CPU prover
+    Cpu,
+    /// Mock proofs, for offline runs and CI; not verifiable on-chain
+    Mock,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum HashingFunction {
+    Keccak,
+    Poseidon,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-struct Transaction {
-    source_chain_id: u64,
-    destination_chain_id: u64,
-    receiver: String,
-    amount: String,
-    block_number: u64,
-    tx_hash: String,
+impl From<HashingFunction> for HashingFunctionDto {
+    fn from(hashing_function: HashingFunction) -> Self {
+        match hashing_function {
+            HashingFunction::Keccak => HashingFunctionDto::Keccak,
+            HashingFunction::Poseidon => HashingFunctionDto::Poseidon,
+        }
+    }
+}
+
+/// The SP1 prover selected by `--prover`. Mock proving runs on the CPU prover.
+enum SettlementProver {
+    Cpu(CpuProver),
+    Network(NetworkProver),
+}
+
+impl SettlementProver {
+    fn new(kind: ProverKind) -> Self {
+        match kind {
+            ProverKind::Network => Self::Network(network_prover()),
+            ProverKind::Cpu => Self::Cpu(ProverClient::builder().cpu().build()),
+            ProverKind::Mock => Self::Cpu(ProverClient::builder().mock().build()),
+        }
+    }
+
+    fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+        match self {
+            Self::Cpu(client) => client.setup(elf),
+            Self::Network(client) => client.setup(elf),
+        }
+    }
+
+    fn execute(&self, elf: &[u8], stdin: &SP1Stdin) -> (SP1PublicValues, ExecutionReport) {
+        match self {
+            Self::Cpu(client) => client.execute(elf, stdin).run().unwrap(),
+            Self::Network(client) => client.execute(elf, stdin).run().unwrap(),
+        }
+    }
+
+    /// Generates a proof. Network proofs are submitted as a request first, and `on_request` is
+    /// called with its id before waiting, so the request survives the process dying.
+    async fn prove(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        system: ProofSystem,
+        on_request: impl FnOnce(FixedBytes<32>),
+    ) -> SP1ProofWithPublicValues {
+        match self {
+            Self::Cpu(client) => {
+                let builder = client.prove(pk, stdin);
+                let builder = match system {
+                    ProofSystem::Groth16 => builder.groth16(),
+                    ProofSystem::Plonk => builder.plonk(),
+                    ProofSystem::Compressed => builder.compressed(),
+                    ProofSystem::Core => builder.core(),
+                };
+                builder.run().expect("failed to generate proof")
+            }
+            Self::Network(client) => {
+                let builder = client.prove(pk, stdin);
+                let builder = match system {
+                    ProofSystem::Groth16 => builder.groth16(),
+                    ProofSystem::Plonk => builder.plonk(),
+                    ProofSystem::Compressed => builder.compressed(),
+                    ProofSystem::Core => builder.core(),
+                };
+                let request_id = builder
+                    .request_async()
+                    .await
+                    .expect("failed to submit proof request");
+                on_request(request_id);
+                client
+                    .wait_proof(request_id, None)
+                    .await
+                    .expect("failed to generate proof")
+            }
+        }
+    }
+}
+
+fn network_prover() -> NetworkProver {
+    let private_key = std::env::var("NETWORK_PRIVATE_KEY")
+        .expect("NETWORK_PRIVATE_KEY must be set for the network prover");
+    ProverClient::builder()
+        .network_for(NetworkMode::Mainnet)
+        .private_key(&private_key)
+        .build()
+}
+
+/// The program inputs fetched from Bankai, saved so a batch can be re-executed or re-proven
+/// offline and deterministically with `--from-inputs`
+#[derive(Serialize, Deserialize)]
+struct BatchInputs {
+    bankai_network: BankaiNetwork,
+    batch_result: ProofWrapper,
+    orders: Vec<Order>,
+    claims: Vec<ClaimedExecution>,
+}
+
+impl BatchInputs {
+    fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, bincode::serialize(self)?)?;
+        Ok(())
+    }
+
+    fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(bincode::deserialize(&std::fs::read(path)?)?)
+    }
+}
+
+/// Everything besides the proof itself that goes into proof.json
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct ProofInputs {
+    proof_system: ProofSystem,
+    #[serde(default)]
+    bankai_network: BankaiNetwork,
+    tree_mode: MerkleTreeMode,
+    orders: Vec<Order>,
+    claims: Vec<ClaimedExecution>,
+    /// The settlement chains' accumulators before this batch is appended
+    accumulators: ChainAccumulators,
+}
+
+/// A submitted network proof request, persisted so `--resume` can finish it
+#[derive(Debug, Deserialize, Serialize)]
+struct ProofRequest {
+    request_id: FixedBytes<32>,
+    #[serde(flatten)]
+    inputs: ProofInputs,
+}
+
+impl ProofRequest {
+    fn path(requests_dir: &str, request_id: FixedBytes<32>) -> PathBuf {
+        Path::new(requests_dir).join(format!("{request_id}.json"))
+    }
+
+    fn save(&self, requests_dir: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(requests_dir)?;
+        let path = Self::path(requests_dir, self.request_id);
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    fn load(
+        requests_dir: &str,
+        request_id: FixedBytes<32>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(Self::path(requests_dir, request_id))?;
+        Ok(serde_json::from_str(&json)?)
+    }
 }
 
 #[tokio::main]
@@ -60,114 +296,478 @@ async fn main() {
     // Parse the command line arguments.
     let args = Args::parse();
 
+    if let Some(request_id) = args.resume {
+        resume(&args, request_id).await;
+        return;
+    }
+
     if args.execute == args.prove {
         eprintln!("Error: You must specify either --execute or --prove");
         std::process::exit(1);
     }
 
-    let private_key = std::env::var("NETWORK_PRIVATE_KEY").unwrap();
-    let client = ProverClient::builder()
-        .network_for(NetworkMode::Mainnet)
-        .private_key(&private_key)
-        .build();
-
-    let exec_rpc = std::env::var("EXECUTION_RPC").ok();
-    let bankai = Bankai::new(Network::Sepolia, exec_rpc.clone(), None);
-
-    // Setup new batch for Sepolia Network
-    let mut bankai_batch = bankai
-        .init_batch(Network::Sepolia, None, HashingFunctionDto::Keccak)
-        .await
-        .unwrap();
-
-    // Add example orders to the batch
-    let orders = load_orders(&args.txs_file).expect("Failed to load orders from JSON file");
+    let client = SettlementProver::new(args.prover);
 
-    // Add evm transactions to the batch
-    for order in orders.clone() {
-        bankai_batch = bankai_batch.evm_tx(order.1.tx_hash);
-    }
-
-    // Execute the batch, generating all proofs for the added transactions
-    let batch_result = bankai_batch.execute().await.unwrap();
+    let BatchInputs {
+        bankai_network,
+        batch_result,
+        orders,
+        claims,
+    } = if args.from_inputs {
+        let inputs =
+            BatchInputs::load(&args.inputs_file).expect("Failed to load the saved batch inputs");
+        check_batch(&inputs.orders, inputs.bankai_network);
+        println!(
+            "Replaying the {} batch saved in {}",
+            inputs.bankai_network, args.inputs_file
+        );
+        inputs
+    } else {
+        let inputs = fetch_batch(&args.txs_file, args.bankai_network, args.hashing_function).await;
+        inputs
+            .save(&args.inputs_file)
+            .expect("Failed to save the batch inputs");
+        inputs
+    };
 
-    let orders: Vec<Order> = orders.iter().map(|(order, _)| order.clone()).collect();
     let mut stdin = SP1Stdin::new();
     stdin.write(&batch_result);
     stdin.write(&orders);
+    stdin.write(&claims);
+    stdin.write(&args.tree_mode);
+
+    // Extend the accumulators persisted by the previous proof run
+    let accumulators =
+        load_accumulators(&args.accumulator_file).expect("Failed to load the order accumulators");
+    stdin.write(&accumulators);
+    stdin.write(&bankai_network);
 
     if args.execute {
         // Execute the program
-        let (output, report) = client.execute(SETTLEMENT_ELF, &stdin).run().unwrap();
+        let (output, report) = client.execute(SETTLEMENT_ELF, &stdin);
         println!("Program executed successfully. {output:?}");
 
-        let output_root = FixedBytes::<32>::from_slice(&output.as_slice()[0..32]);
-        println!("Output Root: {output_root:?}");
+        let output = SettlementOutput::decode(output.as_slice())
+            .expect("Failed to decode settlement output");
+        println!("Output Root: {:?}", output.orders_root);
+        println!(
+            "Bankai Block: {} ({:?})",
+            output.block_number, output.block_hash
+        );
+        println!("Accumulators Root: {:?}", output.accumulators_root);
         // Record the number of cycles executed.
         println!("Number of cycles: {}", report.total_instruction_count());
     } else {
         // Setup the program for proving.
-        let (pk, vk) = client.setup(SETTLEMENT_ELF);
-        println!("vk: {:?}", vk.bytes32());
+        let (pk, vk) = load_or_setup(&args.keys_dir, |elf| client.setup(elf))
+            .expect("Failed to set up the proving keys");
+        let vkey = VkeyArtifact::new(&vk).expect("Invalid vkey hash");
+        vkey.write("vkey.json").expect("Failed to write vkey.json");
+        println!("vk: {}", vkey.vkey);
+
+        let registry =
+            ChainRegistry::load(&args.chains_file).expect("Failed to load the chain registry");
+        check_settlement_chains(&orders, args.proof_system, &registry);
+
+        // Proving takes a while; stop up front if a settlement chain would reject the result
+        if args.proof_system.settles_on_chain() && args.prover != ProverKind::Mock {
+            let mismatches = vkey_mismatches(&vkey, &orders, &registry).await;
+            for mismatch in &mismatches {
+                eprintln!("Vkey mismatch on {mismatch}");
+            }
+            if !mismatches.is_empty() {
+                assert!(
+                    args.allow_vkey_drift,
+                    "{} settlement chains won't accept proofs for program vkey {}; redeploy \
+                     them or pass --allow-vkey-drift to prove anyway",
+                    mismatches.len(),
+                    vkey.vkey
+                );
+                eprintln!("Warning: proving anyway (--allow-vkey-drift)");
+            }
+        }
+
+        let inputs = ProofInputs {
+            proof_system: args.proof_system,
+            bankai_network,
+            tree_mode: args.tree_mode,
+            orders,
+            claims,
+            accumulators,
+        };
 
         // Generate the proof
+        let mut request_path = None;
         let proof = client
-            .prove(&pk, &stdin)
-            .groth16()
-            .run()
-            .expect("failed to generate proof");
+            .prove(&pk, &stdin, args.proof_system, |request_id| {
+                let request = ProofRequest {
+                    request_id,
+                    inputs: inputs.clone(),
+                };
+                let path = request
+                    .save(&args.requests_dir)
+                    .expect("Failed to save the proof request");
+                println!("Submitted proof request {request_id}");
+                println!("If this run is interrupted, finish it with --resume {request_id}");
+                request_path = Some(path);
+            })
+            .await;
+        println!("Generated {} proof", args.proof_system);
 
-        // Read the output.
-        let output_root: FixedBytes<32> =
-            FixedBytes::<32>::from_slice(&proof.public_values.as_slice()[0..32]);
-        println!("Output Root: {output_root:?}");
+        write_artifact(
+            &proof,
+            &vkey,
+            &inputs,
+            args.prover == ProverKind::Mock,
+            &registry,
+            &args.accumulator_file,
+        );
+        if let Some(path) = request_path {
+            std::fs::remove_file(path).expect("Failed to remove the finished proof request");
+        }
+    }
+}
 
-        let merkle_proof = generate_all_proofs(orders.as_slice());
+/// Rejects a batch the settlement program would fail on before any proofs are fetched or
+/// generated: it must hold orders for a single destination chain, the one `bankai_network`
+/// attests to.
+fn check_batch(orders: &[Order], bankai_network: BankaiNetwork) {
+    let destination_chain_id =
+        batch_destination_chain_id(orders).unwrap_or_else(|e| panic!("Invalid batch: {e}"));
+    assert_eq!(
+        destination_chain_id,
+        bankai_network.chain_id(),
+        "Invalid batch: orders are filled on chain {destination_chain_id}, but the {bankai_network} \
+         network is chain {}",
+        bankai_network.chain_id()
+    );
+}
 
-        assert_eq!(
-            merkle_proof.root,
-            FixedBytes::<32>::from_slice(&proof.public_values.as_slice()[0..32])
+/// Refuses, before proving, a proof that some settlement chain in the batch couldn't verify.
+/// Its orders would never settle, and its accumulator would move past the root stored on chain.
+fn check_settlement_chains(orders: &[Order], proof_system: ProofSystem, registry: &ChainRegistry) {
+    if !proof_system.settles_on_chain() {
+        return;
+    }
+    let source_chains: BTreeSet<u64> = orders.iter().map(|order| order.source_chain_id).collect();
+    for chain_id in source_chains {
+        let chain = registry
+            .chains
+            .iter()
+            .find(|chain| chain.chain_id == chain_id)
+            .unwrap_or_else(|| {
+                panic!("Invalid batch: orders settle on chain {chain_id}, which isn't registered")
+            });
+        let supported = chain.family.proof_systems();
+        assert!(
+            supported.contains(&proof_system),
+            "Invalid batch: {} can't verify {proof_system} proofs; prove with {}",
+            chain.display_name,
+            supported
+                .iter()
+                .map(ProofSystem::to_string)
+                .collect::<Vec<_>>()
+                .join(" or ")
         );
+    }
+}
+
+/// Fetches Bankai proofs for every order's fill transaction and receipt
+async fn fetch_batch(
+    txs_file: &str,
+    bankai_network: BankaiNetwork,
+    hashing_function: HashingFunction,
+) -> BatchInputs {
+    // Load the example orders, checking they form a valid batch before fetching anything
+    let orders = load_orders(txs_file).expect("Failed to load orders from JSON file");
+    let batch: Vec<Order> = orders.iter().map(|(order, _)| order.clone()).collect();
+    check_batch(&batch, bankai_network);
+
+    let network = match bankai_network {
+        BankaiNetwork::Sepolia => Network::Sepolia,
+        BankaiNetwork::Mainnet => Network::Mainnet,
+    };
+    let exec_rpc = std::env::var("EXECUTION_RPC").ok();
+    let bankai = Bankai::new(network, exec_rpc.clone(), None);
+
+    // Setup new batch for the selected network
+    println!("Fetching Bankai {bankai_network} proofs ({hashing_function:?} MMR)");
+    let mut bankai_batch = bankai
+        .init_batch(network, None, hashing_function.into())
+        .await
+        .unwrap();
+
+    // Add evm transactions and their receipts to the batch
+    for order in orders.clone() {
+        bankai_batch = bankai_batch
+            .evm_tx(order.1.tx_hash)
+            .evm_receipt(order.1.tx_hash);
+    }
+
+    // Execute the batch, generating all proofs for the added transactions and receipts
+    let batch_result = bankai_batch.execute().await.unwrap();
 
-        // Group proofs by source chain ID
-        let mut proofs_by_chain: std::collections::BTreeMap<u64, Vec<_>> =
-            std::collections::BTreeMap::new();
-        for order_proof in merkle_proof.proofs {
-            let order_hash = order_proof.order.hash();
-            proofs_by_chain
-                .entry(order_proof.order.source_chain_id)
-                .or_default()
-                .push(serde_json::json!({
-                    "order": {
-                        "source_chain_id": order_proof.order.source_chain_id,
-                        "destination_chain_id": order_proof.order.destination_chain_id,
-                        "receiver": order_proof.order.receiver.to_string(),
-                        "amount": order_proof.order.amount.to_string(),
-                        "block_number": order_proof.order.block_number,
-                    },
-                    "order_hash": order_hash.to_string(),
-                    "proof": order_proof.proof.iter()
-                        .map(|h| h.to_string())
-                        .collect::<Vec<_>>(),
-                    "leaf_index": order_proof.leaf_index,
-                }));
+    let (orders, claims) = orders.into_iter().unzip();
+    BatchInputs {
+        bankai_network,
+        batch_result,
+        orders,
+        claims,
+    }
+}
+
+/// Waits for a network proof request saved by an earlier run and writes its proof.json
+async fn resume(args: &Args, request_id: FixedBytes<32>) {
+    let request = ProofRequest::load(&args.requests_dir, request_id)
+        .unwrap_or_else(|e| panic!("No saved inputs for proof request {request_id}: {e}"));
+
+    let registry =
+        ChainRegistry::load(&args.chains_file).expect("Failed to load the chain registry");
+
+    let client = network_prover();
+    let (_, vk) = load_or_setup(&args.keys_dir, |elf| client.setup(elf))
+        .expect("Failed to set up the proving keys");
+    let vkey = VkeyArtifact::new(&vk).expect("Invalid vkey hash");
+
+    println!("Waiting for proof request {request_id}...");
+    let proof = client
+        .wait_proof(request_id, None)
+        .await
+        .expect("failed to generate proof");
+    println!("Generated {} proof", request.inputs.proof_system);
+
+    write_artifact(
+        &proof,
+        &vkey,
+        &request.inputs,
+        false,
+        &registry,
+        &args.accumulator_file,
+    );
+    std::fs::remove_file(ProofRequest::path(&args.requests_dir, request_id))
+        .expect("Failed to remove the finished proof request");
+}
+
+/// Compares the program vkey with the one every settlement chain in the batch verifies
+/// against, returning a line for each chain that would reject the proof. A chain whose vkey
+/// can't be read counts as a mismatch.
+async fn vkey_mismatches(
+    vkey: &VkeyArtifact,
+    orders: &[Order],
+    registry: &ChainRegistry,
+) -> Vec<String> {
+    let source_chains: BTreeSet<u64> = orders.iter().map(|order| order.source_chain_id).collect();
+    let mut mismatches = Vec::new();
+    for chain in &registry.chains {
+        if !source_chains.contains(&chain.chain_id) {
+            continue;
         }
 
-        // Write to file for easy access
-        let fixture = serde_json::json!({
-            "proof": format!("0x{}", hex::encode(proof.bytes())),
-            "publicValues": format!("0x{}", hex::encode(proof.public_values.as_slice())),
-            "vkey": vk.bytes32().to_string(),
-            "merkleRoot": merkle_proof.root.to_string(),
-            "proofsBySourceChain": proofs_by_chain,
-        });
+        // The Solana program verifies against its hardcoded constant, not the stored state
+        if chain.family == ChainFamily::Solana {
+            let hardcoded = FixedBytes::<32>::from_str(BANKAI_VKEY_HASH).unwrap();
+            if hardcoded != vkey.vkey {
+                mismatches.push(format!(
+                    "{}: BANKAI_VKEY_HASH in settlement_lib is {hardcoded}",
+                    chain.display_name
+                ));
+            }
+        }
 
-        let mut file = File::create("proof.json").expect("Failed to create file");
-        file.write_all(fixture.to_string().as_bytes())
-            .expect("Failed to write to file");
+        let deployed = match chain.read_only_client() {
+            Ok(client) => client.program_vkey().await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match deployed {
+            Ok(deployed) if deployed == vkey.vkey => {}
+            Ok(deployed) => mismatches.push(format!(
+                "{}: deployed vkey is {deployed}",
+                chain.display_name
+            )),
+            Err(e) => mismatches.push(format!(
+                "{}: failed to read the deployed vkey: {e}",
+                chain.display_name
+            )),
+        }
+    }
+    mismatches
+}
+
+/// Checks the proof's public values against the inputs, then writes proof.json and advances
+/// the persisted accumulators
+fn write_artifact(
+    proof: &SP1ProofWithPublicValues,
+    vkey: &VkeyArtifact,
+    inputs: &ProofInputs,
+    mock: bool,
+    registry: &ChainRegistry,
+    accumulator_file: &str,
+) {
+    let ProofInputs {
+        proof_system,
+        bankai_network,
+        tree_mode,
+        orders,
+        claims,
+        accumulators: previous,
+    } = inputs;
+
+    // Read the output.
+    let output = SettlementOutput::decode(proof.public_values.as_slice())
+        .expect("Failed to decode settlement output");
+    println!("Output Root: {:?}", output.orders_root);
+    println!(
+        "Bankai Block: {} ({:?})",
+        output.block_number, output.block_hash
+    );
 
-        println!("Successfully generated proof!");
+    let merkle_proof = generate_all_proofs_with_mode(orders.as_slice(), *tree_mode);
+    let nullifier_tree =
+        generate_all_nullifier_proofs(orders.as_slice(), claims.as_slice(), *tree_mode);
+
+    assert_eq!(output.tree_mode, *tree_mode as u8);
+    assert_eq!(output.bankai_network, *bankai_network as u8);
+    assert_eq!(merkle_proof.root, output.orders_root);
+    assert_eq!(nullifier_tree.root, output.nullifiers_root);
+    assert_eq!(output.order_count, orders.len() as u64);
+
+    let mut accumulators = previous.clone();
+    let transitions = accumulators.extend(orders.as_slice());
+    let transitions_tree = accumulator_transitions_tree(&transitions, *tree_mode);
+    assert_eq!(transitions_tree.root(), output.accumulators_root);
+
+    // Only Groth16 and PLONK proofs settle anything, so only they advance the accumulators; a
+    // mock proof can't be sent on-chain either
+    let advance = proof_system.settles_on_chain() && !mock;
+
+    // Another run may have advanced a chain's accumulator since this proof was requested (e.g.
+    // when resuming an older request). The contracts only accept proofs that extend their
+    // stored root, so refuse rather than roll a chain back; the request stays saved for later.
+    // Chains without orders in this batch are left as the other run wrote them.
+    let mut persisted = ChainAccumulators::new();
+    if advance {
+        persisted =
+            load_accumulators(accumulator_file).expect("Failed to load the order accumulators");
+        for transition in &transitions {
+            let chain_id = transition.chain_id;
+            let current = persisted.get(chain_id);
+            let before = previous.get(chain_id);
+            assert!(
+                current == before || current == accumulators.get(chain_id),
+                "{accumulator_file} has chain {chain_id} at {} orders (root {}), but this proof \
+                 extends it from {} orders (root {}); refusing to overwrite it",
+                current.count,
+                current.root(),
+                before.count,
+                before.root()
+            );
+            persisted.0.insert(chain_id, accumulators.get(chain_id));
+        }
     }
+    let accumulator_proofs = transitions
+        .into_iter()
+        .enumerate()
+        .map(|(index, transition)| {
+            let proof = AccumulatorProof {
+                proof: transitions_tree.proof(index),
+                transition,
+            };
+            (proof.transition.chain_id, proof)
+        })
+        .collect();
+
+    // Group proofs by source chain ID
+    let mut proofs_by_source_chain: BTreeMap<u64, Vec<ArtifactOrderProof>> = BTreeMap::new();
+    for (order_proof, nullifier_proof) in merkle_proof.proofs.into_iter().zip(nullifier_tree.proofs)
+    {
+        proofs_by_source_chain
+            .entry(order_proof.order.source_chain_id)
+            .or_default()
+            .push(ArtifactOrderProof {
+                order_hash: order_proof.order.hash(),
+                order: order_proof.order,
+                proof: order_proof.proof,
+                leaf_index: order_proof.leaf_index,
+                nullifier: nullifier_proof.nullifier,
+                nullifier_proof: nullifier_proof.proof,
+            });
+    }
+
+    // Only Groth16 and PLONK proofs have an on-chain encoding; STARK proofs are saved whole
+    // next to proof.json so they can still be verified off-chain.
+    let (proof_bytes, proof_file) = match proof_system {
+        ProofSystem::Groth16 | ProofSystem::Plonk => (proof.bytes(), None),
+        ProofSystem::Compressed | ProofSystem::Core => {
+            proof.save("proof.bin").expect("Failed to save proof");
+            (Vec::new(), Some("proof.bin".to_string()))
+        }
+    };
+
+    let mut artifact = SettlementArtifact {
+        version: SETTLEMENT_ARTIFACT_VERSION,
+        proof_system: *proof_system,
+        proof: proof_bytes.into(),
+        proof_file,
+        mock,
+        vkey: vkey.vkey,
+        public_values: proof.public_values.to_vec().into(),
+        bankai_network: *bankai_network,
+        block_number: output.block_number,
+        block_hash: output.block_hash,
+        tree_mode: *tree_mode,
+        merkle_root: merkle_proof.root,
+        nullifiers_root: nullifier_tree.root,
+        proofs_by_source_chain,
+        accumulators: accumulator_proofs,
+        calldata: BTreeMap::new(),
+    };
+
+    // Starknet can't take the raw Groth16 proof, so convert it once here
+    let mut starknet_chains = registry.by_family(ChainFamily::Starknet).peekable();
+    if *proof_system == ProofSystem::Groth16 && !mock && starknet_chains.peek().is_some() {
+        let calldata = groth16_calldata(&artifact).expect("Failed to build Garaga calldata");
+        for chain in starknet_chains {
+            artifact.calldata.insert(chain.chain_id, calldata.clone());
+        }
+    }
+
+    // Write to file for easy access
+    let mut file = File::create("proof.json").expect("Failed to create file");
+    file.write_all(
+        serde_json::to_string(&artifact)
+            .expect("Failed to serialize proof file")
+            .as_bytes(),
+    )
+    .expect("Failed to write to file");
+
+    // Persist the extended accumulators so the next run continues from these roots
+    if advance {
+        let accumulator_json =
+            serde_json::to_string_pretty(&persisted).expect("Failed to serialize the accumulators");
+        std::fs::write(accumulator_file, accumulator_json)
+            .expect("Failed to write the order accumulators");
+    }
+
+    println!("Successfully generated proof!");
+}
+
+/// Loads the persisted accumulators, starting empty ones on the first run
+fn load_accumulators(path: &str) -> Result<ChainAccumulators, Box<dyn std::error::Error>> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ChainAccumulators::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let value: serde_json::Value = serde_json::from_str(&json)?;
+    if value.get("branch").is_some() {
+        return Err(format!(
+            "{path} holds a single accumulator over every chain's orders, but accumulators are \
+             now kept per settlement chain; move it aside and redeploy or migrate the settlement \
+             contracts, which start every chain from the empty root"
+        )
+        .into());
+    }
+    Ok(serde_json::from_value(value)?)
 }
 
 fn load_orders(
@@ -176,28 +776,8 @@ fn load_orders(
     let txs_json = std::fs::read_to_string(txs_file)?;
     let transactions: Vec<Transaction> = serde_json::from_str(&txs_json)?;
 
-    let orders = transactions
-        .into_iter()
-        .map(|tx| {
-            let order = Order {
-                source_chain_id: tx.source_chain_id,
-                destination_chain_id: tx.destination_chain_id,
-                receiver: Address::from_str(&tx.receiver)
-                    .unwrap_or_else(|_| panic!("Invalid receiver address: {}", tx.receiver)),
-                amount: U256::from_str(&tx.amount)
-                    .unwrap_or_else(|_| panic!("Invalid amount: {}", tx.amount)),
-                block_number: tx.block_number,
-            };
-
-            let claimed_execution = ClaimedExecution {
-                chain_id: tx.destination_chain_id,
-                tx_hash: FixedBytes::<32>::from_hex(&tx.tx_hash)
-                    .unwrap_or_else(|_| panic!("Invalid tx_hash: {}", tx.tx_hash)),
-            };
-
-            (order, claimed_execution)
-        })
-        .collect();
-
-    Ok(orders)
+    transactions
+        .iter()
+        .map(|tx| Ok((tx.order()?, tx.claimed_execution()?)))
+        .collect()
 }
@@ -1,149 +1,223 @@
+use super::error::SettlementError;
+use super::registry::ChainConfig;
+use super::report::Report;
+use alloy_primitives::{Address, FixedBytes, U256};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use settlement_lib::Order;
-use std::collections::BTreeMap;
-
+use settlement_lib::{
+    AccumulatorProof, ArtifactOrderProof, ClaimedExecution, Order, SettlementArtifact,
+    SETTLEMENT_ARTIFACT_VERSION,
+};
+use std::fmt;
+use std::str::FromStr;
+
+/// A settlement contract on one chain. The write methods record what they send into `report`,
+/// which also carries their progress output.
 #[async_trait]
 pub trait ChainClient: Send + Sync {
-    async fn initialize(&self, _dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
-        Err("Initialize not supported for this chain type".into())
+    async fn initialize(
+        &self,
+        _dry_run: bool,
+        _report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        Err(SettlementError::config(
+            "Initialize not supported for this chain type",
+        ))
     }
 
     async fn submit_orders(
         &self,
         transactions: &[Transaction],
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>>;
+        report: &mut Report,
+    ) -> Result<(), SettlementError>;
 
     async fn settle_orders(
         &self,
-        proof_data: &ProofData,
+        proof_data: &SettlementArtifact,
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>>;
+        report: &mut Report,
+    ) -> Result<(), SettlementError>;
 
     async fn reset_orders(
         &self,
-        proof_data: &ProofData,
+        proof_data: &SettlementArtifact,
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>>;
-}
+        report: &mut Report,
+    ) -> Result<(), SettlementError>;
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ProofData {
-    pub proof: String,
-    #[serde(rename = "publicValues")]
-    pub public_values: String,
-    pub vkey: String,
-    #[serde(rename = "merkleRoot")]
-    pub merkle_root: String,
-    #[serde(rename = "proofsBySourceChain")]
-    pub proofs_by_source_chain: BTreeMap<String, Vec<OrderProofJson>>,
+    /// Reads the settlement program vkey the deployed contract verifies proofs against
+    async fn program_vkey(&self) -> Result<FixedBytes<32>, SettlementError>;
+
+    /// Reads what the deployed contract knows about an order
+    async fn order_status(
+        &self,
+        order_hash: FixedBytes<32>,
+    ) -> Result<OrderStatus, SettlementError>;
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct OrderProofJson {
-    pub order: Order,
-    pub order_hash: String,
-    pub proof: Vec<String>,
-    pub leaf_index: usize,
+/// On-chain state of an order, as reported by [`ChainClient::order_status`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    /// Submitted, but not settled yet
+    Submitted,
+    Settled,
+    /// Not known to the contract. Contracts that only store a settled flag (EVM, Starknet)
+    /// can't tell a submitted order apart, so they report every unsettled order as unknown.
+    Unknown,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct Transaction {
-    pub source_chain_id: u64,
-    pub destination_chain_id: u64,
-    pub receiver: String,
-    pub amount: String,
-    pub block_number: u64,
-    pub tx_hash: String,
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OrderStatus::Submitted => "submitted",
+            OrderStatus::Settled => "settled",
+            OrderStatus::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
 }
 
-#[derive(Debug, Clone)]
-pub enum Chain {
-    BaseSepolia,
-    ArbitrumSepolia,
-    SolanaDevnet,
-    StarknetSepolia,
+/// What a proof file settles on one chain: the chain's orders and its accumulator transition.
+/// Each client only encodes and sends these.
+pub struct ChainSettlement<'a> {
+    pub orders: &'a [ArtifactOrderProof],
+    pub accumulator: &'a AccumulatorProof,
 }
 
-impl Chain {
-    pub fn from_name(name: &str) -> Result<Self, String> {
-        match name.to_lowercase().as_str() {
-            "base-sepolia" | "base" => Ok(Chain::BaseSepolia),
-            "arbitrum-sepolia" | "arbitrum" | "arb" => Ok(Chain::ArbitrumSepolia),
-            "solana-devnet" | "solana" => Ok(Chain::SolanaDevnet),
-            "starknet-sepolia" | "starknet" => Ok(Chain::StarknetSepolia),
-            _ => Err(format!(
-                "Unknown chain: {name}. Supported: base-sepolia, arbitrum-sepolia, solana-devnet, starknet-sepolia"
-            )),
+impl<'a> ChainSettlement<'a> {
+    /// Checks the proof file against `config`'s chain and reports the orders it settles there.
+    /// Returns None if the batch has no orders from the chain.
+    pub fn prepare(
+        proof_data: &'a SettlementArtifact,
+        config: &ChainConfig,
+        report: &mut Report,
+    ) -> Result<Option<Self>, SettlementError> {
+        report.info(format!(
+            "⚡ Settling orders on {}...\n",
+            config.display_name
+        ));
+
+        let output = proof_data
+            .settlement_output()
+            .map_err(SettlementError::Encoding)?;
+        report.info(format!(
+            "🧾 Proven root {} at Ethereum block {} ({} orders)\n",
+            output.orders_root, output.block_number, output.order_count
+        ));
+
+        let orders = proof_data.orders_for(config.chain_id);
+        for order_proof in orders {
+            order_proof
+                .check_order_hash()
+                .map_err(SettlementError::Encoding)?;
         }
-    }
 
-    pub fn chain_id(&self) -> u64 {
-        match self {
-            Chain::BaseSepolia => 84532,
-            Chain::ArbitrumSepolia => 421614,
-            Chain::SolanaDevnet => 103,
-            Chain::StarknetSepolia => 393402133025997798, // Numeric representation for Starknet Sepolia
+        // A batch without orders from this chain doesn't move its accumulator, so there is
+        // nothing to send
+        if orders.is_empty() {
+            report.info(format!(
+                "ℹ️  No orders found for {}, nothing to settle",
+                config.display_name
+            ));
+            return Ok(None);
         }
-    }
-
-    pub fn name(&self) -> &str {
-        match self {
-            Chain::BaseSepolia => "Base Sepolia",
-            Chain::ArbitrumSepolia => "Arbitrum Sepolia",
-            Chain::SolanaDevnet => "Solana Devnet",
-            Chain::StarknetSepolia => "Starknet Sepolia",
+        proof_data
+            .require_proof_system(&config.display_name, config.family.proof_systems())
+            .map_err(SettlementError::Config)?;
+        let accumulator = proof_data.accumulator_for(config.chain_id).ok_or_else(|| {
+            SettlementError::encoding("Proof file has no accumulator transition for this chain")
+        })?;
+
+        report.info(format!("📦 Found {} orders to settle", orders.len()));
+        for (i, order) in orders.iter().enumerate() {
+            report.info(format!(
+                "   {}. {} → {} (amount: {} wei)",
+                i + 1,
+                order.order.source_chain_id,
+                order.order.receiver,
+                order.order.amount
+            ));
         }
-    }
 
-    pub fn is_evm(&self) -> bool {
-        matches!(self, Chain::BaseSepolia | Chain::ArbitrumSepolia)
-    }
-
-    pub fn is_solana(&self) -> bool {
-        matches!(self, Chain::SolanaDevnet)
+        Ok(Some(Self {
+            orders,
+            accumulator,
+        }))
     }
+}
 
-    pub fn is_starknet(&self) -> bool {
-        matches!(self, Chain::StarknetSepolia)
+/// Loads a settlement artifact (`proof.json`), rejecting other schema versions.
+///
+/// The version is checked before the artifact is parsed, so a file written by an older script
+/// (e.g. without per-order fill nullifiers) is reported as such rather than as a missing field.
+/// Every order proof must carry its `nullifier`; there is no default for it.
+pub fn load_artifact(path: &str) -> Result<SettlementArtifact, Box<dyn std::error::Error>> {
+    let json = std::fs::read_to_string(path)?;
+    let value: serde_json::Value = serde_json::from_str(&json)?;
+    let version = value.get("version").and_then(serde_json::Value::as_u64);
+    match version {
+        Some(version) if version == u64::from(SETTLEMENT_ARTIFACT_VERSION) => {}
+        Some(version) => {
+            return Err(format!(
+                "{path} has proof file version {version}, expected \
+                 {SETTLEMENT_ARTIFACT_VERSION}; regenerate it with --prove"
+            )
+            .into())
+        }
+        None => {
+            return Err(format!(
+                "{path} has no proof file version, so it was written by an older script; \
+                 regenerate it with --prove"
+            )
+            .into())
+        }
     }
+    let artifact: SettlementArtifact = serde_json::from_value(value)
+        .map_err(|e| format!("{path} is not a valid proof file: {e}"))?;
+    Ok(artifact)
+}
 
-    fn rpc_url(&self) -> Result<String, String> {
-        let env_var = match self {
-            Chain::BaseSepolia => "BASE_SEPOLIA_RPC",
-            Chain::ArbitrumSepolia => "ARB_SEPOLIA_RPC",
-            Chain::SolanaDevnet => "SOLANA_DEVNET_RPC",
-            Chain::StarknetSepolia => "STARKNET_SEPOLIA_RPC",
-        };
-        std::env::var(env_var).map_err(|_| format!("{env_var} environment variable not set"))
-    }
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Transaction {
+    pub source_chain_id: u64,
+    pub destination_chain_id: u64,
+    pub receiver: String,
+    /// ERC-20 token contract, omitted for native ETH fills
+    #[serde(default)]
+    pub token: Option<String>,
+    pub amount: String,
+    pub block_number: u64,
+    /// Last block a fill is accepted in, defaults to `block_number`
+    #[serde(default)]
+    pub fill_deadline: Option<u64>,
+    pub tx_hash: String,
+}
 
-    fn contract_address(&self) -> Result<String, String> {
-        let env_var = match self {
-            Chain::BaseSepolia => "BASE_SEPOLIA_CONTRACT",
-            Chain::ArbitrumSepolia => "ARB_SEPOLIA_CONTRACT",
-            Chain::SolanaDevnet => "SOLANA_DEVNET_PROGRAM",
-            Chain::StarknetSepolia => "STARKNET_SEPOLIA_CONTRACT",
+impl Transaction {
+    /// The order this transaction fills
+    pub fn order(&self) -> Result<Order, SettlementError> {
+        let token = match &self.token {
+            Some(token) => Address::from_str(token).map_err(SettlementError::encoding)?,
+            None => Address::ZERO,
         };
-        std::env::var(env_var).map_err(|_| format!("{env_var} environment variable not set"))
+        Ok(Order {
+            source_chain_id: self.source_chain_id,
+            destination_chain_id: self.destination_chain_id,
+            receiver: Address::from_str(&self.receiver).map_err(SettlementError::encoding)?,
+            token,
+            amount: U256::from_str(&self.amount).map_err(SettlementError::encoding)?,
+            block_number: self.block_number,
+            fill_deadline: self.fill_deadline.unwrap_or(self.block_number),
+        })
     }
-}
-
-pub struct ChainConfig {
-    pub chain: Chain,
-    pub rpc_url: String,
-    pub contract_address: String,
-}
 
-impl ChainConfig {
-    pub fn load(chain: Chain) -> Result<Self, String> {
-        let rpc_url = chain.rpc_url()?;
-        let contract_address = chain.contract_address()?;
-        Ok(Self {
-            chain,
-            rpc_url,
-            contract_address,
+    /// The fill this transaction claims for its order, sent on the destination chain
+    pub fn claimed_execution(&self) -> Result<ClaimedExecution, SettlementError> {
+        Ok(ClaimedExecution {
+            chain_id: self.destination_chain_id,
+            tx_hash: FixedBytes::from_str(&self.tx_hash).map_err(SettlementError::encoding)?,
         })
     }
 }
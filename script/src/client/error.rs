@@ -0,0 +1,76 @@
+use serde::Serialize;
+use std::fmt;
+
+/// How a contract error decoded by a client is reported. Each client maps its contract's
+/// errors to a kind in an explicit table, so a reworded message can't silently change the
+/// classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevertKind {
+    /// Any other revert, e.g. an invalid order
+    Reverted,
+    /// The contract didn't accept the proof, its public values or an order's merkle proof
+    ProofRejected,
+    /// The order or its fill was already used
+    AlreadySettled,
+}
+
+/// Why a [`ChainClient`](super::ChainClient) call failed. Serializes as
+/// `{"kind": "proofRejected", "message": "..."}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum SettlementError {
+    /// The chain entry, signer or proof file can't be used as configured
+    Config(String),
+    /// Calldata or instruction data couldn't be built from the inputs
+    Encoding(String),
+    /// The RPC endpoint failed or timed out
+    Rpc(String),
+    /// The transaction reverted, with the decoded reason where the chain reports one
+    Reverted(String),
+    /// The contract rejected the proof, its public values or an order's merkle proof
+    ProofRejected(String),
+    /// The order was already submitted or settled, or its fill already settled another order
+    AlreadySettled(String),
+    /// The signer can't pay for the transaction
+    InsufficientFunds(String),
+}
+
+impl SettlementError {
+    pub fn config(error: impl fmt::Display) -> Self {
+        SettlementError::Config(error.to_string())
+    }
+
+    pub fn encoding(error: impl fmt::Display) -> Self {
+        SettlementError::Encoding(error.to_string())
+    }
+
+    pub fn rpc(error: impl fmt::Display) -> Self {
+        SettlementError::Rpc(error.to_string())
+    }
+
+    /// Reports a revert that the client decoded as `kind`
+    pub fn revert(kind: RevertKind, reason: impl Into<String>) -> Self {
+        let reason = reason.into();
+        match kind {
+            RevertKind::Reverted => SettlementError::Reverted(reason),
+            RevertKind::ProofRejected => SettlementError::ProofRejected(reason),
+            RevertKind::AlreadySettled => SettlementError::AlreadySettled(reason),
+        }
+    }
+}
+
+impl fmt::Display for SettlementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettlementError::Config(e) => write!(f, "configuration error: {e}"),
+            SettlementError::Encoding(e) => write!(f, "encoding error: {e}"),
+            SettlementError::Rpc(e) => write!(f, "RPC error: {e}"),
+            SettlementError::Reverted(reason) => write!(f, "transaction reverted: {reason}"),
+            SettlementError::ProofRejected(reason) => write!(f, "proof rejected: {reason}"),
+            SettlementError::AlreadySettled(reason) => write!(f, "already settled: {reason}"),
+            SettlementError::InsufficientFunds(e) => write!(f, "insufficient funds: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SettlementError {}
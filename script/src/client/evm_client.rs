@@ -1,11 +1,20 @@
-use super::chain_client::{ChainClient, ChainConfig, OrderProofJson, ProofData, Transaction};
+use super::chain_client::{ChainClient, ChainSettlement, OrderStatus, Transaction};
+use super::error::{RevertKind, SettlementError};
+use super::registry::ChainConfig;
+use super::report::{OrderOutcome, Report, TxReport};
 use alloy_network::EthereumWallet;
 use alloy_primitives::{Address, Bytes, FixedBytes};
+use alloy_provider::transport::TransportError;
 use alloy_provider::{Provider, ProviderBuilder};
 use alloy_rpc_types::TransactionRequest;
 use alloy_signer_local::PrivateKeySigner;
-use alloy_sol_types::{sol, SolCall};
+use alloy_sol_types::{decode_revert_reason, sol, Revert, SolCall};
 use async_trait::async_trait;
+use settlement_lib::{
+    nullifier_leaf, process_multiproof, ArtifactOrderProof, MerkleTree, MerkleTreeMode, MultiProof,
+    SettlementArtifact,
+};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 sol! {
@@ -14,8 +23,10 @@ sol! {
         uint64 sourceChainId;
         uint64 destinationChainId;
         address receiver;
+        address token;
         uint256 amount;
         uint64 blockNumber;
+        uint64 fillDeadline;
     }
 
     #[derive(Debug)]
@@ -23,43 +34,334 @@ sol! {
         bytes32 orderHash;
         bytes32[] proof;
         uint256 leafIndex;
+        bytes32 nullifier;
+        bytes32[] nullifierProof;
+    }
+
+    #[derive(Debug)]
+    struct AccumulatorTransition {
+        uint64 chainId;
+        bytes32 previousRoot;
+        bytes32 root;
+        uint64 count;
+    }
+
+    #[derive(Debug)]
+    struct AccumulatorProof {
+        AccumulatorTransition transition;
+        bytes32[] proof;
     }
 
     function settleOrders(
         bytes calldata publicValues,
         bytes calldata proofBytes,
+        AccumulatorProof memory accumulator,
         OrderProof[] memory orderProofs
     ) external;
 
+    #[derive(Debug)]
+    struct OrderMultiProof {
+        bytes32[] orderHashes;
+        bytes32[] proof;
+        bool[] proofFlags;
+    }
+
+    #[derive(Debug)]
+    struct NullifierMultiProof {
+        uint256[] orderIndices;
+        bytes32[] nullifiers;
+        bytes32[] proof;
+        bool[] proofFlags;
+    }
+
+    function settleOrdersMultiproof(
+        bytes calldata publicValues,
+        bytes calldata proofBytes,
+        AccumulatorProof memory accumulator,
+        OrderMultiProof memory orders,
+        NullifierMultiProof memory nullifiers
+    ) external;
+
     function resetOrders(bytes32[] memory orderHashes) external;
 
+    function vk() external view returns (bytes32);
+
+    function orderMapping(bytes32 orderHash) external view returns (bool);
+
     function submitOrder(SolOrder memory order) external;
+
+    /// Custom errors of the SP1 verifier gateway and the verifiers behind it
+    interface ISP1Verifier {
+        error RouteNotFound(bytes4 selector);
+        error RouteIsFrozen(bytes4 selector);
+        error WrongVerifierSelector(bytes4 received, bytes4 expected);
+        error InvalidProof();
+        error ProofInvalid();
+        error PublicInputNotInField();
+    }
+}
+
+/// `require` reasons of `OrderMerkleVerifier`; the tests check them against the contract source.
+/// Reasons not listed here (or panics) are reported as plain reverts.
+const CONTRACT_REVERTS: [(&str, RevertKind); 16] = [
+    ("Unsupported Bankai network", RevertKind::Reverted),
+    ("Wrong chain id set", RevertKind::Reverted),
+    ("Invalid fill window", RevertKind::Reverted),
+    ("Order already exists", RevertKind::AlreadySettled),
+    (
+        "Fill already used by another order",
+        RevertKind::AlreadySettled,
+    ),
+    ("Invalid merkle proof", RevertKind::ProofRejected),
+    (
+        "Multiproofs need the standard tree",
+        RevertKind::ProofRejected,
+    ),
+    ("Nullifier count mismatch", RevertKind::ProofRejected),
+    ("Invalid nullifier pairing", RevertKind::ProofRejected),
+    ("Invalid nullifier proof", RevertKind::ProofRejected),
+    ("Wrong settlement chain", RevertKind::ProofRejected),
+    ("Invalid accumulator proof", RevertKind::ProofRejected),
+    ("Accumulator root mismatch", RevertKind::ProofRejected),
+    ("Unsupported output version", RevertKind::ProofRejected),
+    ("Unsupported tree mode", RevertKind::ProofRejected),
+    ("Wrong Bankai network", RevertKind::ProofRejected),
+];
+
+/// Classifies a failed JSON-RPC request, decoding the revert reason when the node returns one
+fn rpc_error(error: TransportError) -> SettlementError {
+    let Some(payload) = error.as_error_resp() else {
+        return SettlementError::rpc(error);
+    };
+
+    use ISP1Verifier::ISP1VerifierErrors;
+
+    if let Some(verifier_error) = payload.as_decoded_interface_error::<ISP1VerifierErrors>() {
+        let reason = match verifier_error {
+            ISP1VerifierErrors::RouteNotFound(e) => {
+                format!("no SP1 verifier route for selector {}", e.selector)
+            }
+            ISP1VerifierErrors::RouteIsFrozen(e) => {
+                format!("SP1 verifier route {} is frozen", e.selector)
+            }
+            ISP1VerifierErrors::WrongVerifierSelector(e) => format!(
+                "proof has verifier selector {}, expected {}",
+                e.received, e.expected
+            ),
+            ISP1VerifierErrors::InvalidProof(_) | ISP1VerifierErrors::ProofInvalid(_) => {
+                "SP1 verifier rejected the proof".to_string()
+            }
+            ISP1VerifierErrors::PublicInputNotInField(_) => {
+                "SP1 verifier rejected the public values: not in the scalar field".to_string()
+            }
+        };
+        return SettlementError::ProofRejected(reason);
+    }
+    if let Some(Revert { reason }) = payload.as_decoded_error::<Revert>() {
+        let kind = CONTRACT_REVERTS
+            .iter()
+            .find(|(known, _)| *known == reason)
+            .map_or(RevertKind::Reverted, |(_, kind)| *kind);
+        return SettlementError::revert(kind, reason);
+    }
+    if let Some(data) = payload.as_revert_data() {
+        let reason =
+            decode_revert_reason(&data).unwrap_or_else(|| format!("undecoded revert data {data}"));
+        return SettlementError::revert(RevertKind::Reverted, reason);
+    }
+    if payload.message.contains("insufficient funds") {
+        return SettlementError::InsufficientFunds(payload.message.to_string());
+    }
+    if payload.message.contains("revert") {
+        return SettlementError::revert(RevertKind::Reverted, payload.message.to_string());
+    }
+    SettlementError::rpc(error)
 }
 
 pub struct EvmClient {
     config: ChainConfig,
-    private_key: String,
+    /// None for a read-only client
+    private_key: Option<String>,
 }
 
 impl EvmClient {
-    pub fn new(
-        config: ChainConfig,
-        private_key: String,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(config: ChainConfig, private_key: String) -> Result<Self, SettlementError> {
         Ok(Self {
             config,
-            private_key,
+            private_key: Some(private_key),
         })
     }
 
-    fn create_provider(&self) -> Result<impl Provider, Box<dyn std::error::Error>> {
-        let signer: PrivateKeySigner = self.private_key.parse()?;
+    /// A client that only reads the contract, so it needs no signing key
+    pub fn read_only(config: ChainConfig) -> Result<Self, SettlementError> {
+        Ok(Self {
+            config,
+            private_key: None,
+        })
+    }
+
+    fn create_provider(&self) -> Result<impl Provider, SettlementError> {
+        let private_key = self.private_key.as_ref().ok_or_else(|| {
+            SettlementError::Config(format!(
+                "{} client is read-only; set {} to send transactions",
+                self.config.display_name, self.config.signer
+            ))
+        })?;
+        let signer: PrivateKeySigner = private_key.parse().map_err(SettlementError::config)?;
         let wallet = EthereumWallet::from(signer);
-        let provider = ProviderBuilder::new()
-            .wallet(wallet)
-            .connect_http(self.config.rpc_url.parse()?);
+        let provider = ProviderBuilder::new().wallet(wallet).connect_http(
+            self.config
+                .rpc_url
+                .parse()
+                .map_err(SettlementError::config)?,
+        );
         Ok(provider)
     }
+
+    fn contract_address(&self) -> Result<Address, SettlementError> {
+        Address::from_str(&self.config.contract_address).map_err(SettlementError::config)
+    }
+
+    /// Sends a transaction and waits for its receipt, printing the hash as soon as it's known
+    async fn send(
+        &self,
+        provider: &impl Provider,
+        tx: TransactionRequest,
+        report: &Report,
+    ) -> Result<TxReport, SettlementError> {
+        let pending_tx = provider.send_transaction(tx).await.map_err(rpc_error)?;
+        let tx_hash = *pending_tx.tx_hash();
+        let explorer_url = self.config.explorer_tx(tx_hash);
+
+        report.info(format!("   Tx hash: {tx_hash}"));
+        if let Some(url) = &explorer_url {
+            report.info(format!("   Explorer: {url}"));
+        }
+        report.info("   Waiting for confirmation...");
+
+        let receipt = pending_tx
+            .get_receipt()
+            .await
+            .map_err(SettlementError::rpc)?;
+        if !receipt.status() {
+            return Err(SettlementError::Reverted(format!(
+                "transaction {tx_hash} reverted"
+            )));
+        }
+
+        let fee = u128::from(receipt.gas_used) * receipt.effective_gas_price;
+        Ok(TxReport {
+            tx_hash: tx_hash.to_string(),
+            explorer_url,
+            block_number: receipt.block_number,
+            gas_used: Some(receipt.gas_used),
+            fee: Some(fee.to_string()),
+        })
+    }
+
+    /// Builds one orders multiproof and one nullifiers multiproof covering `orders_to_settle`,
+    /// replacing the per-order sibling paths.
+    fn multiproof_call(
+        proof_data: &SettlementArtifact,
+        orders_to_settle: &[ArtifactOrderProof],
+        public_values: Bytes,
+        proof_bytes: Bytes,
+        accumulator: AccumulatorProof,
+    ) -> Result<settleOrdersMultiproofCall, SettlementError> {
+        let output = proof_data
+            .settlement_output()
+            .map_err(SettlementError::Encoding)?;
+
+        // Rebuild every leaf of the batch, across all source chains, in leaf order
+        let mut batch: Vec<&ArtifactOrderProof> = proof_data
+            .proofs_by_source_chain
+            .values()
+            .flatten()
+            .collect();
+        batch.sort_by_key(|op| op.leaf_index);
+        if batch.len() as u64 != output.order_count
+            || batch.iter().enumerate().any(|(i, op)| op.leaf_index != i)
+        {
+            return Err(SettlementError::encoding(
+                "Proof file does not list every order of the batch",
+            ));
+        }
+
+        let order_hashes: Vec<FixedBytes<32>> = batch.iter().map(|op| op.order.hash()).collect();
+        let nullifiers: Vec<FixedBytes<32>> = batch.iter().map(|op| op.nullifier).collect();
+        let nullifier_hashes: Vec<FixedBytes<32>> = order_hashes
+            .iter()
+            .zip(&nullifiers)
+            .map(|(order_hash, nullifier)| nullifier_leaf(*order_hash, *nullifier))
+            .collect();
+
+        let indices: Vec<usize> = orders_to_settle.iter().map(|op| op.leaf_index).collect();
+        let order_tree = MerkleTree::new(&order_hashes, MerkleTreeMode::Standard);
+        let nullifier_tree = MerkleTree::new(&nullifier_hashes, MerkleTreeMode::Standard);
+        let order_multiproof = order_tree
+            .multiproof(&indices)
+            .ok_or_else(|| SettlementError::encoding("Failed to build the orders multiproof"))?;
+        let nullifier_multiproof = nullifier_tree.multiproof(&indices).ok_or_else(|| {
+            SettlementError::encoding("Failed to build the nullifiers multiproof")
+        })?;
+
+        // Catch a mismatch locally rather than in a reverted transaction
+        let verify = |multiproof: &MultiProof, tree: &MerkleTree, root| {
+            let leaves: Vec<FixedBytes<32>> = multiproof
+                .leaf_indices
+                .iter()
+                .map(|&i| tree.leaves()[i])
+                .collect();
+            process_multiproof(&leaves, &multiproof.proof, &multiproof.proof_flags) == Some(root)
+        };
+        if !verify(&order_multiproof, &order_tree, output.orders_root)
+            || !verify(
+                &nullifier_multiproof,
+                &nullifier_tree,
+                output.nullifiers_root,
+            )
+        {
+            return Err(SettlementError::encoding(
+                "Multiproof does not match the committed roots",
+            ));
+        }
+
+        let positions: BTreeMap<usize, usize> = order_multiproof
+            .leaf_indices
+            .iter()
+            .enumerate()
+            .map(|(position, &index)| (index, position))
+            .collect();
+
+        Ok(settleOrdersMultiproofCall {
+            publicValues: public_values,
+            proofBytes: proof_bytes,
+            accumulator,
+            orders: OrderMultiProof {
+                orderHashes: order_multiproof
+                    .leaf_indices
+                    .iter()
+                    .map(|&i| order_hashes[i])
+                    .collect(),
+                proof: order_multiproof.proof,
+                proofFlags: order_multiproof.proof_flags,
+            },
+            nullifiers: NullifierMultiProof {
+                orderIndices: nullifier_multiproof
+                    .leaf_indices
+                    .iter()
+                    .map(|i| alloy_primitives::U256::from(positions[i]))
+                    .collect(),
+                nullifiers: nullifier_multiproof
+                    .leaf_indices
+                    .iter()
+                    .map(|&i| nullifiers[i])
+                    .collect(),
+                proof: nullifier_multiproof.proof,
+                proofFlags: nullifier_multiproof.proof_flags,
+            },
+        })
+    }
 }
 
 #[async_trait]
@@ -68,64 +370,64 @@ impl ChainClient for EvmClient {
         &self,
         transactions: &[Transaction],
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("📝 Submitting orders to {}...\n", self.config.chain.name());
+        report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        report.info(format!(
+            "📝 Submitting orders to {}...\n",
+            self.config.display_name
+        ));
 
         let filtered_txs: Vec<&Transaction> = transactions
             .iter()
-            .filter(|tx| tx.source_chain_id == self.config.chain.chain_id())
+            .filter(|tx| tx.source_chain_id == self.config.chain_id)
             .collect();
 
         if filtered_txs.is_empty() {
-            println!(
+            report.info(format!(
                 "ℹ️  No orders found with source {}",
-                self.config.chain.name()
-            );
+                self.config.display_name
+            ));
             return Ok(());
         }
 
-        println!("📦 Found {} orders to submit", filtered_txs.len());
+        report.info(format!("📦 Found {} orders to submit", filtered_txs.len()));
         for (i, tx) in filtered_txs.iter().enumerate() {
-            println!(
+            report.info(format!(
                 "   {}. From chain {} → {} (amount: {} wei, block: {})",
                 i + 1,
                 tx.source_chain_id,
                 tx.receiver,
                 tx.amount,
                 tx.block_number
-            );
+            ));
         }
-        println!();
+        report.info("");
 
-        println!("📋 Contract: {}", self.config.contract_address);
-        println!();
+        report.info(format!("📋 Contract: {}", self.config.contract_address));
+        report.info("");
 
         let provider = self.create_provider()?;
-        let contract_address = Address::from_str(&self.config.contract_address)?;
+        let contract_address = self.contract_address()?;
 
         for (i, tx) in filtered_txs.iter().enumerate() {
-            println!("📤 [{}/{}] Submitting order...", i + 1, filtered_txs.len());
-
-            let receiver = Address::from_str(&tx.receiver)?;
-            let amount = alloy_primitives::U256::from_str(&tx.amount)?;
-
-            let order = settlement_lib::Order {
-                source_chain_id: tx.source_chain_id,
-                destination_chain_id: tx.destination_chain_id,
-                receiver,
-                amount,
-                block_number: tx.block_number,
-            };
+            report.info(format!(
+                "📤 [{}/{}] Submitting order...",
+                i + 1,
+                filtered_txs.len()
+            ));
 
+            let order = tx.order()?;
             let order_hash = order.hash();
-            println!("   Order hash: 0x{}", hex::encode(order_hash));
+            report.info(format!("   Order hash: 0x{}", hex::encode(order_hash)));
 
             let sol_order = SolOrder {
                 sourceChainId: order.source_chain_id,
                 destinationChainId: order.destination_chain_id,
                 receiver: order.receiver,
+                token: order.token,
                 amount: order.amount,
                 blockNumber: order.block_number,
+                fillDeadline: order.fill_deadline,
             };
 
             let call = submitOrderCall { order: sol_order };
@@ -136,30 +438,32 @@ impl ChainClient for EvmClient {
                 .input(calldata.into());
 
             if dry_run {
-                println!("   ✅ Dry run - transaction prepared\n");
+                report.info("   ✅ Dry run - transaction prepared\n");
+                report.order(order_hash, OrderOutcome::Prepared, None);
                 continue;
             }
 
-            let pending_tx = provider.send_transaction(tx_req).await?;
-            let tx_hash = pending_tx.tx_hash();
-
-            println!("   Tx hash: {tx_hash}");
-            println!("   Waiting for confirmation...");
-
-            let receipt = pending_tx.get_receipt().await?;
-
-            if receipt.status() {
-                println!("   ✅ Success (Gas: {})\n", receipt.gas_used);
-            } else {
-                println!("   ❌ Failed\n");
-                return Err("Transaction reverted".into());
+            match self.send(&provider, tx_req, report).await {
+                Ok(sent) => {
+                    report.info(format!(
+                        "   ✅ Success (Gas: {})\n",
+                        sent.gas_used.unwrap_or_default()
+                    ));
+                    report.order(order_hash, OrderOutcome::Submitted, Some(&sent.tx_hash));
+                    report.transaction(sent);
+                }
+                Err(e) => {
+                    report.info(format!("   ❌ Failed: {e}\n"));
+                    report.order(order_hash, OrderOutcome::Failed, None);
+                    return Err(e);
+                }
             }
         }
 
         if dry_run {
-            println!("🔍 Dry run completed - no transactions sent");
+            report.info("🔍 Dry run completed - no transactions sent");
         } else {
-            println!("✅ All orders submitted successfully!");
+            report.info("✅ All orders submitted successfully!");
         }
 
         Ok(())
@@ -167,186 +471,303 @@ impl ChainClient for EvmClient {
 
     async fn settle_orders(
         &self,
-        proof_data: &ProofData,
+        proof_data: &SettlementArtifact,
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("⚡ Settling orders on {}...\n", self.config.chain.name());
-
-        let mut orders_to_settle: Vec<OrderProofJson> = Vec::new();
-
-        for order_proofs in proof_data.proofs_by_source_chain.values() {
-            for order_proof in order_proofs {
-                if order_proof.order.source_chain_id == self.config.chain.chain_id() {
-                    orders_to_settle.push(order_proof.clone());
-                }
-            }
-        }
-
-        if orders_to_settle.is_empty() {
-            println!("ℹ️  No orders found for {}", self.config.chain.name());
+        report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        let Some(ChainSettlement {
+            orders: orders_to_settle,
+            accumulator,
+        }) = ChainSettlement::prepare(proof_data, &self.config, report)?
+        else {
             return Ok(());
-        }
-
-        println!("📦 Found {} orders to settle", orders_to_settle.len());
-        for (i, order) in orders_to_settle.iter().enumerate() {
-            println!(
-                "   {}. {} → {} (amount: {} wei)",
-                i + 1,
-                order.order.source_chain_id,
-                order.order.receiver,
-                order.order.amount
-            );
-        }
-        println!();
+        };
+        let accumulator = AccumulatorProof {
+            transition: AccumulatorTransition {
+                chainId: accumulator.transition.chain_id,
+                previousRoot: accumulator.transition.previous_root,
+                root: accumulator.transition.root,
+                count: accumulator.transition.count,
+            },
+            proof: accumulator.proof.clone(),
+        };
+        report.info("");
 
         let order_proofs: Vec<OrderProof> = orders_to_settle
             .iter()
-            .map(|op| {
-                let order_hash_bytes = hex::decode(op.order_hash.trim_start_matches("0x")).unwrap();
-                let order_hash = FixedBytes::<32>::from_slice(&order_hash_bytes);
-
-                let proof: Vec<FixedBytes<32>> = op
-                    .proof
-                    .iter()
-                    .map(|p| {
-                        let bytes = hex::decode(p.trim_start_matches("0x")).unwrap();
-                        FixedBytes::<32>::from_slice(&bytes)
-                    })
-                    .collect();
-
-                OrderProof {
-                    orderHash: order_hash,
-                    proof,
-                    leafIndex: alloy_primitives::U256::from(op.leaf_index),
-                }
+            .map(|op| OrderProof {
+                orderHash: op.order_hash,
+                proof: op.proof.clone(),
+                leafIndex: alloy_primitives::U256::from(op.leaf_index),
+                nullifier: op.nullifier,
+                nullifierProof: op.nullifier_proof.clone(),
             })
             .collect();
 
-        let public_values_bytes = hex::decode(proof_data.public_values.trim_start_matches("0x"))?;
-        let public_values = Bytes::from(public_values_bytes);
-
-        let proof_bytes_vec = hex::decode(proof_data.proof.trim_start_matches("0x"))?;
-        let proof_bytes = Bytes::from(proof_bytes_vec);
-
-        let call = settleOrdersCall {
-            publicValues: public_values.clone(),
-            proofBytes: proof_bytes.clone(),
-            orderProofs: order_proofs,
-        };
-
-        let calldata = call.abi_encode();
+        let public_values = proof_data.public_values.clone();
+        let proof_bytes = proof_data.proof.clone();
+
+        // One multiproof per tree is smaller than a path per order once several orders share nodes
+        let calldata =
+            if proof_data.tree_mode == MerkleTreeMode::Standard && orders_to_settle.len() > 1 {
+                report.info(format!(
+                    "🌳 Using multiproofs for {} orders",
+                    orders_to_settle.len()
+                ));
+                Self::multiproof_call(
+                    proof_data,
+                    orders_to_settle,
+                    public_values.clone(),
+                    proof_bytes.clone(),
+                    accumulator,
+                )?
+                .abi_encode()
+            } else {
+                settleOrdersCall {
+                    publicValues: public_values.clone(),
+                    proofBytes: proof_bytes.clone(),
+                    accumulator,
+                    orderProofs: order_proofs,
+                }
+                .abi_encode()
+            };
 
-        println!("📋 Transaction Details:");
-        println!("   Contract: {}", self.config.contract_address);
-        println!("   Public values: {} bytes", public_values.len());
-        println!("   Proof: {} bytes", proof_bytes.len());
-        println!("   Calldata: {} bytes", calldata.len());
-        println!();
+        report.info("📋 Transaction Details:");
+        report.info(format!("   Contract: {}", self.config.contract_address));
+        report.info(format!("   Public values: {} bytes", public_values.len()));
+        report.info(format!("   Proof: {} bytes", proof_bytes.len()));
+        report.info(format!("   Calldata: {} bytes", calldata.len()));
+        report.info("");
 
-        let contract_address = Address::from_str(&self.config.contract_address)?;
+        let contract_address = self.contract_address()?;
         let tx = TransactionRequest::default()
             .to(contract_address)
             .input(calldata.into());
 
+        let order_hashes = orders_to_settle.iter().map(|op| op.order_hash);
         if dry_run {
-            println!("✅ Dry run mode - transaction prepared successfully but not sent");
+            report.info("✅ Dry run mode - transaction prepared successfully but not sent");
+            report.orders(order_hashes, OrderOutcome::Prepared, None);
             return Ok(());
         }
 
         let provider = self.create_provider()?;
 
-        println!("📤 Sending transaction...");
+        report.info("📤 Sending transaction...");
 
-        let pending_tx = provider.send_transaction(tx).await?;
-        let tx_hash = pending_tx.tx_hash();
+        let sent = match self.send(&provider, tx, report).await {
+            Ok(sent) => sent,
+            Err(e) => {
+                report.info(format!("\n❌ Transaction failed: {e}"));
+                report.orders(order_hashes, OrderOutcome::Failed, None);
+                return Err(e);
+            }
+        };
+
+        report.info("\n✅ Settlement successful!");
+        report.info(format!(
+            "   Block: {}",
+            sent.block_number.unwrap_or_default()
+        ));
+        report.info(format!(
+            "   Gas used: {}",
+            sent.gas_used.unwrap_or_default()
+        ));
+        report.orders(order_hashes, OrderOutcome::Settled, Some(&sent.tx_hash));
+        report.transaction(sent);
+
+        Ok(())
+    }
 
-        println!("   Tx hash: {tx_hash}");
-        println!("   Waiting for confirmation...");
+    async fn program_vkey(&self) -> Result<FixedBytes<32>, SettlementError> {
+        let provider = ProviderBuilder::new().connect_http(
+            self.config
+                .rpc_url
+                .parse()
+                .map_err(SettlementError::config)?,
+        );
+        let tx = TransactionRequest::default()
+            .to(self.contract_address()?)
+            .input(vkCall {}.abi_encode().into());
+        let result = provider.call(tx).await.map_err(rpc_error)?;
+        vkCall::abi_decode_returns(&result).map_err(SettlementError::encoding)
+    }
 
-        let receipt = pending_tx.get_receipt().await?;
+    async fn order_status(
+        &self,
+        order_hash: FixedBytes<32>,
+    ) -> Result<OrderStatus, SettlementError> {
+        let provider = ProviderBuilder::new().connect_http(
+            self.config
+                .rpc_url
+                .parse()
+                .map_err(SettlementError::config)?,
+        );
+        let tx = TransactionRequest::default()
+            .to(self.contract_address()?)
+            .input(
+                orderMappingCall {
+                    orderHash: order_hash,
+                }
+                .abi_encode()
+                .into(),
+            );
+        let result = provider.call(tx).await.map_err(rpc_error)?;
 
-        if receipt.status() {
-            let block_num = receipt.block_number.unwrap_or_default();
-            let gas_used = receipt.gas_used;
-            println!("\n✅ Settlement successful!");
-            println!("   Block: {block_num}");
-            println!("   Gas used: {gas_used}");
+        // orderMapping is false for submitted and never-seen orders alike
+        if orderMappingCall::abi_decode_returns(&result).map_err(SettlementError::encoding)? {
+            Ok(OrderStatus::Settled)
         } else {
-            println!("\n❌ Transaction failed!");
-            return Err("Transaction reverted".into());
+            Ok(OrderStatus::Unknown)
         }
-
-        Ok(())
     }
 
     async fn reset_orders(
         &self,
-        proof_data: &ProofData,
+        proof_data: &SettlementArtifact,
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("🔄 Resetting orders on {}...\n", self.config.chain.name());
-
-        let chain_id_str = self.config.chain.chain_id().to_string();
+        report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        report.info(format!(
+            "🔄 Resetting orders on {}...\n",
+            self.config.display_name
+        ));
 
         let order_hashes: Vec<FixedBytes<32>> = proof_data
-            .proofs_by_source_chain
-            .get(&chain_id_str)
-            .map(|orders| {
-                orders
-                    .iter()
-                    .map(|op| {
-                        let bytes = hex::decode(op.order_hash.trim_start_matches("0x")).unwrap();
-                        FixedBytes::<32>::from_slice(&bytes)
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
+            .orders_for(self.config.chain_id)
+            .iter()
+            .map(|op| op.order_hash)
+            .collect();
 
         if order_hashes.is_empty() {
-            println!("⏭️  No orders to reset");
+            report.info("⏭️  No orders to reset");
             return Ok(());
         }
 
-        println!(
+        report.info(format!(
             "📍 {} ({} orders)",
-            self.config.chain.name(),
+            self.config.display_name,
             order_hashes.len()
-        );
-        println!("   Contract: {}", self.config.contract_address);
+        ));
+        report.info(format!("   Contract: {}", self.config.contract_address));
 
         let call = resetOrdersCall {
-            orderHashes: order_hashes,
+            orderHashes: order_hashes.clone(),
         };
         let calldata = call.abi_encode();
 
-        let contract_address = Address::from_str(&self.config.contract_address)?;
+        let contract_address = self.contract_address()?;
         let tx = TransactionRequest::default()
             .to(contract_address)
             .input(calldata.into());
 
         if dry_run {
-            println!("   ✅ Dry run - transaction prepared");
+            report.info("   ✅ Dry run - transaction prepared");
+            report.orders(order_hashes, OrderOutcome::Prepared, None);
             return Ok(());
         }
 
         let provider = self.create_provider()?;
 
-        println!("   📤 Sending transaction...");
+        report.info("   📤 Sending transaction...");
+
+        match self.send(&provider, tx, report).await {
+            Ok(sent) => {
+                report.info(format!(
+                    "   ✅ Success (Gas: {})",
+                    sent.gas_used.unwrap_or_default()
+                ));
+                report.orders(order_hashes, OrderOutcome::Reset, Some(&sent.tx_hash));
+                report.transaction(sent);
+            }
+            Err(e) => {
+                report.info(format!("   ❌ Failed: {e}"));
+                report.orders(order_hashes, OrderOutcome::Failed, None);
+                return Err(e);
+            }
+        }
 
-        let pending_tx = provider.send_transaction(tx).await?;
-        let tx_hash = pending_tx.tx_hash();
+        Ok(())
+    }
+}
 
-        println!("   Tx: {tx_hash}");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::keccak256;
+    use std::collections::BTreeSet;
+
+    const CONTRACT: &str = include_str!("../../../contracts/evm/src/OrderMerkleVerifier.sol");
+
+    /// The gateway and the Groth16 and PLONK verifiers it routes to
+    const SP1_CONTRACTS: [&str; 4] = [
+        include_str!("../../../contracts/evm/lib/sp1-contracts/contracts/src/ISP1VerifierGateway.sol"),
+        include_str!(
+            "../../../contracts/evm/lib/sp1-contracts/contracts/src/v4.0.0-rc.3/SP1VerifierGroth16.sol"
+        ),
+        include_str!(
+            "../../../contracts/evm/lib/sp1-contracts/contracts/src/v4.0.0-rc.3/Groth16Verifier.sol"
+        ),
+        include_str!(
+            "../../../contracts/evm/lib/sp1-contracts/contracts/src/v4.0.0-rc.3/SP1VerifierPlonk.sol"
+        ),
+    ];
+
+    /// The reason of every `require` in a Solidity source, i.e. the call's last string literal
+    fn require_reasons(source: &str) -> BTreeSet<&str> {
+        source
+            .split("require(")
+            .skip(1)
+            .map(|call| {
+                let call = &call[..call.find(");").expect("unterminated require")];
+                let end = call.rfind('"').expect("require without a reason");
+                let start = call[..end].rfind('"').expect("require without a reason");
+                &call[start + 1..end]
+            })
+            .collect()
+    }
 
-        let receipt = pending_tx.get_receipt().await?;
+    /// Selectors of the `error` declarations in a Solidity source
+    fn error_selectors(source: &str) -> BTreeSet<[u8; 4]> {
+        source
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("error "))
+            .map(|declaration| {
+                let (name, params) = declaration.split_once('(').expect("malformed error");
+                let (params, _) = params.split_once(')').expect("malformed error");
+                let types: Vec<&str> = params
+                    .split(',')
+                    .filter_map(|param| param.split_whitespace().next())
+                    .collect();
+                let selector = keccak256(format!("{name}({})", types.join(",")));
+                [selector[0], selector[1], selector[2], selector[3]]
+            })
+            .collect()
+    }
 
-        if receipt.status() {
-            println!("   ✅ Success (Gas: {})", receipt.gas_used);
-        } else {
-            println!("   ❌ Failed");
-            return Err("Transaction reverted".into());
-        }
+    #[test]
+    fn contract_reverts_match_the_contract() {
+        let known: BTreeSet<&str> = CONTRACT_REVERTS.iter().map(|(reason, _)| *reason).collect();
+        assert_eq!(
+            known.len(),
+            CONTRACT_REVERTS.len(),
+            "duplicate revert reasons"
+        );
+        assert_eq!(known, require_reasons(CONTRACT));
+    }
 
-        Ok(())
+    #[test]
+    fn verifier_errors_match_the_sp1_contracts() {
+        let declared: BTreeSet<[u8; 4]> = SP1_CONTRACTS
+            .into_iter()
+            .flat_map(error_selectors)
+            .collect();
+        for selector in ISP1Verifier::ISP1VerifierErrors::SELECTORS {
+            assert!(
+                declared.contains(selector),
+                "{} isn't an error of the SP1 contracts",
+                Bytes::copy_from_slice(selector)
+            );
+        }
     }
 }
@@ -1,9 +1,15 @@
 pub mod chain_client;
+pub mod error;
 pub mod evm_client;
+pub mod registry;
+pub mod report;
 pub mod solana_client;
 pub mod starknet_client;
 
-pub use chain_client::{Chain, ChainClient, ChainConfig, ProofData, Transaction};
+pub use chain_client::{load_artifact, ChainClient, OrderStatus, Transaction};
+pub use error::SettlementError;
 pub use evm_client::EvmClient;
+pub use registry::{ChainConfig, ChainFamily, ChainRegistry, DEFAULT_CHAINS_FILE};
+pub use report::{ChainReport, ChainStatus, OrderOutcome, OutputFormat, Report, TxReport};
 pub use solana_client::SolanaClient;
 pub use starknet_client::StarknetClient;
@@ -0,0 +1,320 @@
+use super::chain_client::ChainClient;
+use super::evm_client::EvmClient;
+use super::solana_client::SolanaClient;
+use super::starknet_client::StarknetClient;
+use serde::Deserialize;
+use settlement_lib::ProofSystem;
+use std::fmt;
+
+/// Default location of the chain registry, relative to the working directory
+pub const DEFAULT_CHAINS_FILE: &str = "chains.toml";
+
+/// The client family a chain is driven with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainFamily {
+    Evm,
+    Solana,
+    Starknet,
+}
+
+impl ChainFamily {
+    /// Proof systems the family's settlement contracts can verify
+    pub fn proof_systems(self) -> &'static [ProofSystem] {
+        match self {
+            ChainFamily::Evm => &[ProofSystem::Groth16, ProofSystem::Plonk],
+            ChainFamily::Solana | ChainFamily::Starknet => &[ProofSystem::Groth16],
+        }
+    }
+}
+
+/// A settlement chain as declared in `chains.toml`.
+///
+/// `rpc_url`, `contract_address` and `account` may be given as `${VAR}`, in which case they're
+/// read from the environment by [`ChainConfig::resolve`], so endpoints and deployments can stay
+/// in `.env`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainConfig {
+    /// Canonical CLI name, e.g. `base-sepolia`
+    pub name: String,
+    /// Name used in output, e.g. `Base Sepolia`
+    pub display_name: String,
+    /// Other names the chain can be selected by
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub family: ChainFamily,
+    /// Chain id orders use as their `source_chain_id`
+    pub chain_id: u64,
+    pub rpc_url: String,
+    /// Settlement contract address, or program id on Solana
+    pub contract_address: String,
+    /// Transaction URL template, with `{tx}` replaced by the transaction hash or signature
+    #[serde(default)]
+    pub explorer_url: Option<String>,
+    /// Environment variable holding the signing key
+    pub signer: String,
+    /// Account contract the signer controls, for chains with account abstraction (Starknet)
+    #[serde(default)]
+    pub account: Option<String>,
+}
+
+impl ChainConfig {
+    /// Returns true if `name` is the chain's name or one of its aliases
+    pub fn matches(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+
+    /// Reads every `${VAR}` value from the environment
+    pub fn resolve(&self) -> Result<Self, String> {
+        Ok(Self {
+            rpc_url: expand_env(&self.rpc_url)?,
+            contract_address: expand_env(&self.contract_address)?,
+            account: self.account.as_deref().map(expand_env).transpose()?,
+            ..self.clone()
+        })
+    }
+
+    /// Reads the signing key from the environment variable named by `signer`
+    pub fn signer_key(&self) -> Result<String, String> {
+        std::env::var(&self.signer).map_err(|_| {
+            format!(
+                "{} must be set to sign transactions on {}",
+                self.signer, self.display_name
+            )
+        })
+    }
+
+    /// Creates the chain's client, signing with `signer_key` if given and otherwise with the key
+    /// named by `signer`. The Starknet client reads its key when it sends.
+    pub fn client(
+        &self,
+        signer_key: Option<String>,
+    ) -> Result<Box<dyn ChainClient>, Box<dyn std::error::Error>> {
+        let config = self.resolve()?;
+
+        match config.family {
+            ChainFamily::Evm => {
+                let private_key = match signer_key {
+                    Some(private_key) => private_key,
+                    None => config.signer_key()?,
+                };
+                Ok(Box::new(EvmClient::new(config, private_key)?))
+            }
+            ChainFamily::Solana => {
+                let solana_private_key = match signer_key {
+                    Some(solana_private_key) => solana_private_key,
+                    None => config.signer_key()?,
+                };
+                Ok(Box::new(SolanaClient::new(config, solana_private_key)?))
+            }
+            ChainFamily::Starknet => Ok(Box::new(StarknetClient::new(config)?)),
+        }
+    }
+
+    /// Creates a client that only reads the chain's contract, e.g. its vkey or order statuses,
+    /// so no signing key needs to be set
+    pub fn read_only_client(&self) -> Result<Box<dyn ChainClient>, Box<dyn std::error::Error>> {
+        let config = self.resolve()?;
+
+        match config.family {
+            ChainFamily::Evm => Ok(Box::new(EvmClient::read_only(config)?)),
+            ChainFamily::Solana => Ok(Box::new(SolanaClient::read_only(config)?)),
+            ChainFamily::Starknet => Ok(Box::new(StarknetClient::new(config)?)),
+        }
+    }
+
+    /// Explorer link for a transaction, if the chain has an explorer configured
+    pub fn explorer_tx(&self, tx: impl fmt::Display) -> Option<String> {
+        self.explorer_url
+            .as_ref()
+            .map(|url| url.replace("{tx}", &tx.to_string()))
+    }
+}
+
+/// Expands a `${VAR}` value from the environment, leaving any other value as is
+fn expand_env(value: &str) -> Result<String, String> {
+    match value
+        .strip_prefix("${")
+        .and_then(|rest| rest.strip_suffix('}'))
+    {
+        Some(env_var) => {
+            std::env::var(env_var).map_err(|_| format!("{env_var} environment variable not set"))
+        }
+        None => Ok(value.to_string()),
+    }
+}
+
+/// Every chain the CLI can settle on, loaded from `chains.toml`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainRegistry {
+    #[serde(rename = "chain")]
+    pub chains: Vec<ChainConfig>,
+}
+
+impl ChainRegistry {
+    /// Loads the registry from `path`, see [`ChainRegistry::parse`]
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read chain registry {path}: {e}"))?;
+        Self::parse(&contents)
+    }
+
+    /// Parses the registry, rejecting duplicate names, aliases and chain ids
+    pub fn parse(contents: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let registry: Self = toml::from_str(contents)?;
+
+        for (i, chain) in registry.chains.iter().enumerate() {
+            for other in &registry.chains[i + 1..] {
+                if other.chain_id == chain.chain_id {
+                    return Err(format!(
+                        "{} and {} share chain id {}",
+                        chain.name, other.name, chain.chain_id
+                    )
+                    .into());
+                }
+                if let Some(name) = std::iter::once(&other.name)
+                    .chain(&other.aliases)
+                    .find(|name| chain.matches(name))
+                {
+                    return Err(
+                        format!("{name} refers to both {} and {}", chain.name, other.name).into(),
+                    );
+                }
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// Looks a chain up by name or alias
+    pub fn find(&self, name: &str) -> Result<&ChainConfig, String> {
+        self.chains
+            .iter()
+            .find(|chain| chain.matches(name))
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.chains.iter().map(|c| c.name.as_str()).collect();
+                format!("Unknown chain: {name}. Supported: {}", names.join(", "))
+            })
+    }
+
+    /// Every chain for `all`, otherwise the named chain
+    pub fn select(&self, name: &str) -> Result<Vec<&ChainConfig>, String> {
+        if name.eq_ignore_ascii_case("all") {
+            Ok(self.chains.iter().collect())
+        } else {
+            Ok(vec![self.find(name)?])
+        }
+    }
+
+    /// Chains driven by the given client family
+    pub fn by_family(&self, family: ChainFamily) -> impl Iterator<Item = &ChainConfig> {
+        self.chains
+            .iter()
+            .filter(move |chain| chain.family == family)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(name: &str, aliases: &str, family: &str, chain_id: u64) -> String {
+        format!(
+            r#"
+[[chain]]
+name = "{name}"
+display_name = "{name}"
+aliases = [{aliases}]
+family = "{family}"
+chain_id = {chain_id}
+rpc_url = "http://localhost"
+contract_address = "0x0"
+signer = "PRIVATE_KEY"
+"#
+        )
+    }
+
+    #[test]
+    fn shipped_registry_loads() {
+        let registry = ChainRegistry::parse(include_str!("../../chains.toml")).unwrap();
+        assert!(registry.by_family(ChainFamily::Evm).count() > 0);
+        assert!(registry.by_family(ChainFamily::Solana).count() > 0);
+        assert!(registry.by_family(ChainFamily::Starknet).count() > 0);
+    }
+
+    #[test]
+    fn chains_are_found_by_name_or_alias() {
+        let registry = ChainRegistry::parse(&format!(
+            "{}{}",
+            chain("base-sepolia", r#""base""#, "evm", 84532),
+            chain("arbitrum-sepolia", r#""arbitrum", "arb""#, "evm", 421614),
+        ))
+        .unwrap();
+
+        assert_eq!(registry.find("base-sepolia").unwrap().chain_id, 84532);
+        assert_eq!(registry.find("BASE").unwrap().chain_id, 84532);
+        assert_eq!(registry.find("arb").unwrap().chain_id, 421614);
+        assert_eq!(
+            registry.find("optimism").unwrap_err(),
+            "Unknown chain: optimism. Supported: base-sepolia, arbitrum-sepolia"
+        );
+        assert_eq!(registry.select("all").unwrap().len(), 2);
+        assert_eq!(
+            registry.select("arbitrum").unwrap()[0].name,
+            "arbitrum-sepolia"
+        );
+    }
+
+    #[test]
+    fn duplicate_chain_ids_are_rejected() {
+        let error = ChainRegistry::parse(&format!(
+            "{}{}",
+            chain("base-sepolia", "", "evm", 84532),
+            chain("base-testnet", "", "evm", 84532),
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "base-sepolia and base-testnet share chain id 84532"
+        );
+    }
+
+    #[test]
+    fn duplicate_aliases_are_rejected() {
+        let error = ChainRegistry::parse(&format!(
+            "{}{}",
+            chain("base-sepolia", r#""base""#, "evm", 84532),
+            chain("base-mainnet", r#""BASE""#, "evm", 8453),
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "BASE refers to both base-sepolia and base-mainnet"
+        );
+
+        // An alias may not shadow another chain's name either
+        let error = ChainRegistry::parse(&format!(
+            "{}{}",
+            chain("base-sepolia", "", "evm", 84532),
+            chain("base-mainnet", r#""base-sepolia""#, "evm", 8453),
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "base-sepolia refers to both base-sepolia and base-mainnet"
+        );
+    }
+
+    #[test]
+    fn unknown_families_are_rejected() {
+        let error = ChainRegistry::parse(&chain("cosmoshub", "", "cosmos", 1)).unwrap_err();
+        assert!(
+            error.to_string().contains("unknown variant `cosmos`"),
+            "{error}"
+        );
+    }
+}
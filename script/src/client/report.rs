@@ -0,0 +1,222 @@
+use super::error::SettlementError;
+use super::registry::ChainConfig;
+use alloy_primitives::FixedBytes;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// How the CLI writes its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Progress lines for a terminal
+    #[default]
+    Text,
+    /// One JSON document on stdout once the command finishes
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unknown output format: {s}. Supported: text, json")),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => f.write_str("text"),
+            OutputFormat::Json => f.write_str("json"),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Prints a progress line, which only text output shows
+    pub fn print(&self, line: impl fmt::Display) {
+        if *self == OutputFormat::Text {
+            println!("{line}");
+        }
+    }
+}
+
+/// What happened to one order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderOutcome {
+    Submitted,
+    Settled,
+    Reset,
+    /// Already on-chain, so nothing was sent
+    Skipped,
+    /// Dry run: the transaction was built but not sent
+    Prepared,
+    Failed,
+}
+
+/// A transaction sent to a settlement chain
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxReport {
+    /// Transaction hash, or signature on Solana
+    pub tx_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
+    /// Block the transaction was included in, or slot on Solana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    /// Fee paid, in the chain's smallest native unit (wei, lamports)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderReport {
+    pub order_hash: FixedBytes<32>,
+    pub outcome: OrderOutcome,
+    /// The transaction in [`ChainReport::transactions`] that carried the order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+}
+
+/// How a command went on one chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainStatus {
+    Succeeded,
+    Failed,
+    /// Stopped because another chain failed first
+    Cancelled,
+}
+
+/// Everything a command did on one chain, as printed by `--output json`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainReport {
+    /// Registry name, absent for source chains without a registry entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain: Option<String>,
+    #[serde(skip)]
+    pub display_name: String,
+    pub chain_id: u64,
+    pub status: ChainStatus,
+    pub dry_run: bool,
+    pub orders: Vec<OrderReport>,
+    pub transactions: Vec<TxReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<SettlementError>,
+}
+
+impl ChainReport {
+    fn new(config: &ChainConfig, dry_run: bool) -> Self {
+        Self {
+            chain: Some(config.name.clone()),
+            display_name: config.display_name.clone(),
+            chain_id: config.chain_id,
+            status: ChainStatus::Succeeded,
+            dry_run,
+            orders: Vec::new(),
+            transactions: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// A chain the command couldn't start on
+    pub fn failed(config: &ChainConfig, dry_run: bool, error: SettlementError) -> Self {
+        Self {
+            status: ChainStatus::Failed,
+            error: Some(error),
+            ..Self::new(config, dry_run)
+        }
+    }
+
+    /// A source chain without an entry in the chain registry
+    pub fn unregistered(chain_id: u64, dry_run: bool) -> Self {
+        Self {
+            chain: None,
+            display_name: format!("Source chain {chain_id}"),
+            chain_id,
+            status: ChainStatus::Failed,
+            dry_run,
+            orders: Vec::new(),
+            transactions: Vec::new(),
+            error: Some(SettlementError::config("no entry in the chain registry")),
+        }
+    }
+
+    /// A chain whose run was cancelled before it finished
+    pub fn cancelled(config: &ChainConfig, dry_run: bool) -> Self {
+        Self {
+            status: ChainStatus::Cancelled,
+            ..Self::new(config, dry_run)
+        }
+    }
+}
+
+/// The reporting layer between a [`ChainClient`](super::ChainClient) and the CLI: records the
+/// transactions sent and the orders they carried, and prints progress lines in text output.
+pub struct Report {
+    format: OutputFormat,
+    chain: ChainReport,
+}
+
+impl Report {
+    pub fn new(format: OutputFormat, config: &ChainConfig, dry_run: bool) -> Self {
+        Self {
+            format,
+            chain: ChainReport::new(config, dry_run),
+        }
+    }
+
+    /// Prints a progress line, which only text output shows
+    pub fn info(&self, line: impl fmt::Display) {
+        self.format.print(line);
+    }
+
+    pub fn transaction(&mut self, tx: TxReport) {
+        self.chain.transactions.push(tx);
+    }
+
+    pub fn order(
+        &mut self,
+        order_hash: FixedBytes<32>,
+        outcome: OrderOutcome,
+        tx_hash: Option<&str>,
+    ) {
+        self.chain.orders.push(OrderReport {
+            order_hash,
+            outcome,
+            tx_hash: tx_hash.map(str::to_string),
+        });
+    }
+
+    /// Records several orders carried by the same transaction
+    pub fn orders(
+        &mut self,
+        order_hashes: impl IntoIterator<Item = FixedBytes<32>>,
+        outcome: OrderOutcome,
+        tx_hash: Option<&str>,
+    ) {
+        for order_hash in order_hashes {
+            self.order(order_hash, outcome, tx_hash);
+        }
+    }
+
+    /// Closes the report with the client call's result
+    pub fn finish(self, result: Result<(), SettlementError>) -> ChainReport {
+        let mut chain = self.chain;
+        if let Err(error) = result {
+            chain.status = ChainStatus::Failed;
+            chain.error = Some(error);
+        }
+        chain
+    }
+}
@@ -1,49 +1,126 @@
-use super::chain_client::{ChainClient, ChainConfig, OrderProofJson, ProofData, Transaction};
+use super::chain_client::{ChainClient, ChainSettlement, OrderStatus, Transaction};
+use super::error::{RevertKind, SettlementError};
+use super::registry::ChainConfig;
+use super::report::{OrderOutcome, Report, TxReport};
+use alloy_primitives::FixedBytes;
 use async_trait::async_trait;
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_client::rpc_client::RpcClient;
+use settlement_lib::{SettlementArtifact, BANKAI_VKEY_HASH, SOLANA_PROGRAM_ERRORS};
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
-    instruction::{AccountMeta, Instruction},
+    instruction::{AccountMeta, Instruction, InstructionError},
     pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
     system_program::ID as SYSTEM_PROGRAM_ID,
-    transaction::Transaction as SolanaTransaction,
+    transaction::{Transaction as SolanaTransaction, TransactionError},
 };
 use std::fs;
 use std::str::FromStr;
 
+/// Anchor numbers a program's `#[error_code]` variants from this code on
+const ANCHOR_ERROR_CODE_OFFSET: u32 = 6000;
+
+/// How a failure with one of the program's `SettlementError` variants is reported
+fn program_error_kind(variant: &str) -> RevertKind {
+    match variant {
+        "NullifierAlreadyUsed" => RevertKind::AlreadySettled,
+        "InvalidFillWindow" | "StateAlreadyMigrated" => RevertKind::Reverted,
+        _ => RevertKind::ProofRejected,
+    }
+}
+
+/// Size of a state account created before the accumulator root was stored: the Anchor
+/// discriminator, the vkey hash and the bump. `initialize` migrates such an account.
+const LEGACY_STATE_LEN: usize = 8 + 32 + 1;
+
+/// Classifies a failed RPC request, mapping the program's Anchor error codes to their messages
+fn rpc_error(error: ClientError) -> SettlementError {
+    match error.get_transaction_error() {
+        Some(TransactionError::InstructionError(_, InstructionError::Custom(code))) => {
+            match code
+                .checked_sub(ANCHOR_ERROR_CODE_OFFSET)
+                .and_then(|index| SOLANA_PROGRAM_ERRORS.get(index as usize))
+            {
+                Some((variant, message)) => SettlementError::revert(
+                    program_error_kind(variant),
+                    format!("{message} (Anchor error {code})"),
+                ),
+                None => SettlementError::revert(
+                    RevertKind::Reverted,
+                    format!("custom program error {code:#x}"),
+                ),
+            }
+        }
+        Some(
+            TransactionError::InsufficientFundsForFee
+            | TransactionError::InstructionError(_, InstructionError::InsufficientFunds),
+        ) => SettlementError::InsufficientFunds(error.to_string()),
+        Some(e) => SettlementError::revert(RevertKind::Reverted, e.to_string()),
+        None => SettlementError::rpc(error),
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Clone)]
 pub struct Order {
     pub source_chain_id: u64,
     pub destination_chain_id: u64,
     pub receiver: [u8; 20],
+    pub token: [u8; 20],
     pub amount: [u8; 32],
     pub block_number: u64,
+    pub fill_deadline: u64,
+}
+
+impl From<&settlement_lib::Order> for Order {
+    fn from(order: &settlement_lib::Order) -> Self {
+        Self {
+            source_chain_id: order.source_chain_id,
+            destination_chain_id: order.destination_chain_id,
+            receiver: order.receiver.into(),
+            token: order.token.into(),
+            amount: order.amount.to_be_bytes(),
+            block_number: order.block_number,
+            fill_deadline: order.fill_deadline,
+        }
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone)]
-pub struct OrderProof {
-    pub order: Order,
-    pub order_hash: [u8; 32],
-    pub proof: Vec<[u8; 32]>,
+pub struct AccumulatorTransition {
+    pub chain_id: u64,
+    pub previous_root: [u8; 32],
+    pub root: [u8; 32],
+    pub count: u64,
 }
 
-fn compute_order_hash(order: &Order) -> [u8; 32] {
-    use solana_sdk::keccak::hashv;
+impl From<&settlement_lib::AccumulatorTransition> for AccumulatorTransition {
+    fn from(transition: &settlement_lib::AccumulatorTransition) -> Self {
+        Self {
+            chain_id: transition.chain_id,
+            previous_root: transition.previous_root.0,
+            root: transition.root.0,
+            count: transition.count,
+        }
+    }
+}
 
-    let mut w1 = [0u8; 32];
-    w1[24..].copy_from_slice(&order.source_chain_id.to_be_bytes());
-    let mut w2 = [0u8; 32];
-    w2[24..].copy_from_slice(&order.destination_chain_id.to_be_bytes());
-    let mut w3 = [0u8; 32];
-    w3[12..].copy_from_slice(&order.receiver);
-    let mut w5 = [0u8; 32];
-    w5[24..].copy_from_slice(&order.block_number.to_be_bytes());
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct AccumulatorProof {
+    pub transition: AccumulatorTransition,
+    pub proof: Vec<[u8; 32]>,
+}
 
-    hashv(&[&w1, &w2, &w3, &order.amount, &w5]).to_bytes()
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct OrderProof {
+    pub order: Order,
+    pub order_hash: [u8; 32],
+    pub proof: Vec<[u8; 32]>,
+    pub nullifier: [u8; 32],
+    pub nullifier_proof: Vec<[u8; 32]>,
 }
 
 fn get_discriminator(namespace: &str, name: &str) -> [u8; 8] {
@@ -55,29 +132,45 @@ fn get_discriminator(namespace: &str, name: &str) -> [u8; 8] {
 
 pub struct SolanaClient {
     config: ChainConfig,
-    keypair: Keypair,
+    /// None for a read-only client
+    keypair: Option<Keypair>,
     rpc_client: RpcClient,
     program_id: Pubkey,
 }
 
 impl SolanaClient {
-    pub fn new(
-        config: ChainConfig,
-        solana_private_key: String,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
-        let keypair = load_solana_keypair(&solana_private_key)?;
+    pub fn new(config: ChainConfig, solana_private_key: String) -> Result<Self, SettlementError> {
+        let keypair = load_solana_keypair(&solana_private_key).map_err(SettlementError::config)?;
+        Ok(Self {
+            keypair: Some(keypair),
+            ..Self::read_only(config)?
+        })
+    }
+
+    /// A client that only reads the program's accounts, so it needs no signing key
+    pub fn read_only(config: ChainConfig) -> Result<Self, SettlementError> {
         let rpc_client =
             RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
-        let program_id = Pubkey::from_str(&config.contract_address)?;
+        let program_id =
+            Pubkey::from_str(&config.contract_address).map_err(SettlementError::config)?;
 
         Ok(Self {
             config,
-            keypair,
+            keypair: None,
             rpc_client,
             program_id,
         })
     }
 
+    fn keypair(&self) -> Result<&Keypair, SettlementError> {
+        self.keypair.as_ref().ok_or_else(|| {
+            SettlementError::Config(format!(
+                "{} client is read-only; set {} to send transactions",
+                self.config.display_name, self.config.signer
+            ))
+        })
+    }
+
     fn get_state_pda(&self) -> (Pubkey, u8) {
         Pubkey::find_program_address(&[b"state"], &self.program_id)
     }
@@ -85,6 +178,107 @@ impl SolanaClient {
     fn get_order_pda(&self, order_hash: &[u8; 32]) -> (Pubkey, u8) {
         Pubkey::find_program_address(&[b"order", order_hash], &self.program_id)
     }
+
+    fn get_nullifier_pda(&self, nullifier: &[u8; 32]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"nullifier", nullifier], &self.program_id)
+    }
+
+    /// Builds a `settle_orders` instruction, passing each order's PDA and nullifier PDA as
+    /// remaining accounts
+    fn settle_instruction(
+        &self,
+        public_inputs: &[u8],
+        proof: &[u8],
+        accumulator: &AccumulatorProof,
+        order_proofs: &[OrderProof],
+    ) -> Result<Instruction, SettlementError> {
+        let discriminator = get_discriminator("global", "settle_orders");
+        let mut data = Vec::new();
+        data.extend_from_slice(&discriminator);
+        let encoded = [
+            borsh::to_vec(public_inputs),
+            borsh::to_vec(proof),
+            borsh::to_vec(accumulator),
+            borsh::to_vec(order_proofs),
+        ];
+        for field in encoded {
+            data.extend_from_slice(&field.map_err(SettlementError::encoding)?);
+        }
+
+        let (state_pda, _) = self.get_state_pda();
+        let mut accounts = vec![
+            AccountMeta::new(state_pda, false),
+            AccountMeta::new(self.keypair()?.pubkey(), true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+        for order_proof in order_proofs {
+            let (order_pda, _) = self.get_order_pda(&order_proof.order_hash);
+            let (nullifier_pda, _) = self.get_nullifier_pda(&order_proof.nullifier);
+            accounts.push(AccountMeta::new(order_pda, false));
+            accounts.push(AccountMeta::new(nullifier_pda, false));
+        }
+
+        Ok(Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        })
+    }
+
+    /// Signs, sends and confirms a transaction, printing its signature once confirmed
+    async fn send(
+        &self,
+        instructions: &[Instruction],
+        report: &Report,
+    ) -> Result<TxReport, SettlementError> {
+        let recent_blockhash = self
+            .rpc_client
+            .get_latest_blockhash()
+            .await
+            .map_err(rpc_error)?;
+        let keypair = self.keypair()?;
+        let transaction = SolanaTransaction::new_signed_with_payer(
+            instructions,
+            Some(&keypair.pubkey()),
+            &[keypair],
+            recent_blockhash,
+        );
+        let fee = self
+            .rpc_client
+            .get_fee_for_message(transaction.message())
+            .await
+            .map_err(rpc_error)?;
+
+        let signature = self
+            .rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .await
+            .map_err(rpc_error)?;
+        let explorer_url = self.config.explorer_tx(signature);
+        report.info(format!("   Tx signature: {signature}"));
+        if let Some(url) = &explorer_url {
+            report.info(format!("   Explorer: {url}"));
+        }
+
+        let slot = self
+            .rpc_client
+            .get_signature_statuses(&[signature])
+            .await
+            .map_err(rpc_error)?
+            .value
+            .into_iter()
+            .next()
+            .flatten()
+            .map(|status| status.slot);
+
+        Ok(TxReport {
+            tx_hash: signature.to_string(),
+            explorer_url,
+            block_number: slot,
+            gas_used: None,
+            fee: Some(fee.to_string()),
+        })
+    }
 }
 
 fn load_solana_keypair(private_key_str: &str) -> Result<Keypair, Box<dyn std::error::Error>> {
@@ -122,40 +316,54 @@ fn load_solana_keypair(private_key_str: &str) -> Result<Keypair, Box<dyn std::er
 
 #[async_trait]
 impl ChainClient for SolanaClient {
-    async fn initialize(&self, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
-        println!("🔧 Initializing Solana program state...\n");
+    async fn initialize(&self, dry_run: bool, report: &mut Report) -> Result<(), SettlementError> {
+        report.info("🔧 Initializing Solana program state...\n");
 
-        println!("👤 Sender: {}", self.keypair.pubkey());
-        println!("📋 Program ID: {}", self.program_id);
-        println!();
+        report.info(format!("👤 Sender: {}", self.keypair()?.pubkey()));
+        report.info(format!("📋 Program ID: {}", self.program_id));
+        report.info("");
 
-        // Parse the vkey hash from the constant in the program
-        const BANKAI_VKEY_HASH: &str =
-            "0x001ef62344ca35708c7af9dc5cda28683244720d303d480b47d82136ede2b8ed";
         let vkey_hash_hex = BANKAI_VKEY_HASH.trim_start_matches("0x");
-        let vkey_hash_bytes = hex::decode(vkey_hash_hex)?;
+        let vkey_hash_bytes = hex::decode(vkey_hash_hex).map_err(SettlementError::encoding)?;
         let mut vkey_hash = [0u8; 32];
         vkey_hash.copy_from_slice(&vkey_hash_bytes);
 
         let (state_pda, _) = self.get_state_pda();
 
-        println!("   State PDA: {state_pda}");
-        println!("   VKey Hash: {BANKAI_VKEY_HASH}");
-        println!();
+        report.info(format!("   State PDA: {state_pda}"));
+        report.info(format!("   VKey Hash: {BANKAI_VKEY_HASH}"));
+        report.info("");
+
+        // A state account from before the accumulator root was stored can't be initialized
+        // again, so it is grown in place and its accumulator starts from the empty tree
+        let legacy_state = self
+            .rpc_client
+            .get_account_with_commitment(&state_pda, self.rpc_client.commitment())
+            .await
+            .map_err(rpc_error)?
+            .value
+            .is_some_and(|account| account.data.len() == LEGACY_STATE_LEN);
+        if legacy_state {
+            report.info("ℹ️  State account uses the old layout, migrating it instead");
+        }
 
         if dry_run {
-            println!("✅ Dry run mode - transaction prepared successfully but not sent");
+            report.info("✅ Dry run mode - transaction prepared successfully but not sent");
             return Ok(());
         }
 
-        let discriminator = get_discriminator("global", "initialize");
         let mut instruction_data = Vec::new();
-        instruction_data.extend_from_slice(&discriminator);
-        instruction_data.extend_from_slice(&borsh::to_vec(&vkey_hash)?);
+        if legacy_state {
+            instruction_data.extend_from_slice(&get_discriminator("global", "migrate_state"));
+        } else {
+            instruction_data.extend_from_slice(&get_discriminator("global", "initialize"));
+            instruction_data
+                .extend_from_slice(&borsh::to_vec(&vkey_hash).map_err(SettlementError::encoding)?);
+        }
 
         let accounts = vec![
             AccountMeta::new(state_pda, false),
-            AccountMeta::new(self.keypair.pubkey(), true),
+            AccountMeta::new(self.keypair()?.pubkey(), true),
             AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
         ];
 
@@ -165,25 +373,23 @@ impl ChainClient for SolanaClient {
             data: instruction_data,
         };
 
-        println!("📤 Sending transaction...");
+        report.info("📤 Sending transaction...");
 
-        let recent_blockhash = self.rpc_client.get_latest_blockhash()?;
-        let transaction = SolanaTransaction::new_signed_with_payer(
-            &[instruction],
-            Some(&self.keypair.pubkey()),
-            &[&self.keypair],
-            recent_blockhash,
-        );
-
-        match self.rpc_client.send_and_confirm_transaction(&transaction) {
-            Ok(signature) => {
-                println!("   Tx signature: {signature}");
-                println!("\n✅ Program initialized successfully!");
-                println!("\nYou can now submit orders using: cargo run --bin cli -- submit solana-devnet");
+        match self.send(&[instruction], report).await {
+            Ok(sent) => {
+                if legacy_state {
+                    report.info("\n✅ Program state migrated successfully!");
+                } else {
+                    report.info("\n✅ Program initialized successfully!");
+                }
+                report.info(
+                    "\nYou can now submit orders using: cargo run --bin cli -- submit solana-devnet",
+                );
+                report.transaction(sent);
             }
             Err(e) => {
-                println!("\n❌ Initialization failed: {e}");
-                return Err(e.into());
+                report.info(format!("\n❌ Initialization failed: {e}"));
+                return Err(e);
             }
         }
 
@@ -194,93 +400,85 @@ impl ChainClient for SolanaClient {
         &self,
         transactions: &[Transaction],
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("📝 Submitting orders to {}...\n", self.config.chain.name());
+        report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        report.info(format!(
+            "📝 Submitting orders to {}...\n",
+            self.config.display_name
+        ));
 
-        println!("👤 Sender: {}", self.keypair.pubkey());
-        println!("📋 Program ID: {}", self.program_id);
-        println!();
+        report.info(format!("👤 Sender: {}", self.keypair()?.pubkey()));
+        report.info(format!("📋 Program ID: {}", self.program_id));
+        report.info("");
 
         let filtered_txs: Vec<&Transaction> = transactions
             .iter()
-            .filter(|tx| tx.source_chain_id == self.config.chain.chain_id())
+            .filter(|tx| tx.source_chain_id == self.config.chain_id)
             .collect();
 
         if filtered_txs.is_empty() {
-            println!(
+            report.info(format!(
                 "ℹ️  No orders found with source {}",
-                self.config.chain.name()
-            );
+                self.config.display_name
+            ));
             return Ok(());
         }
 
-        println!("📦 Found {} orders to submit", filtered_txs.len());
+        report.info(format!("📦 Found {} orders to submit", filtered_txs.len()));
         for (i, tx) in filtered_txs.iter().enumerate() {
-            println!(
+            report.info(format!(
                 "   {}. From chain {} → {} (amount: {} wei, block: {})",
                 i + 1,
                 tx.source_chain_id,
                 tx.receiver,
                 tx.amount,
                 tx.block_number
-            );
+            ));
         }
-        println!();
+        report.info("");
 
         let (state_pda, _) = self.get_state_pda();
 
         for (i, tx) in filtered_txs.iter().enumerate() {
-            println!("📤 [{}/{}] Submitting order...", i + 1, filtered_txs.len());
-
-            let receiver_hex = tx.receiver.trim_start_matches("0x");
-            let receiver_bytes = hex::decode(receiver_hex)?;
-            if receiver_bytes.len() != 20 {
-                return Err(
-                    format!("Invalid receiver address length: {}", receiver_bytes.len()).into(),
-                );
-            }
-            let mut receiver = [0u8; 20];
-            receiver.copy_from_slice(&receiver_bytes);
-
-            let amount = alloy_primitives::U256::from_str(&tx.amount)?;
-            let amount_bytes: [u8; 32] = amount.to_be_bytes();
-
-            let order = Order {
-                source_chain_id: tx.source_chain_id,
-                destination_chain_id: tx.destination_chain_id,
-                receiver,
-                amount: amount_bytes,
-                block_number: tx.block_number,
-            };
+            report.info(format!(
+                "📤 [{}/{}] Submitting order...",
+                i + 1,
+                filtered_txs.len()
+            ));
 
-            let order_hash = compute_order_hash(&order);
-            println!("   Order hash: 0x{}", hex::encode(order_hash));
+            let order = tx.order()?;
+            let order_hash = order.hash().0;
+            report.info(format!("   Order hash: 0x{}", hex::encode(order_hash)));
 
             let (order_pda, _) = self.get_order_pda(&order_hash);
 
             // Check if order already exists
-            if let Ok(account) = self.rpc_client.get_account(&order_pda) {
+            if let Ok(account) = self.rpc_client.get_account(&order_pda).await {
                 if account.lamports > 0 {
-                    println!("   ⏭️  Order already submitted, skipping\n");
+                    report.info("   ⏭️  Order already submitted, skipping\n");
+                    report.order(order_hash.into(), OrderOutcome::Skipped, None);
                     continue;
                 }
             }
 
             if dry_run {
-                println!("   ✅ Dry run - transaction prepared\n");
+                report.info("   ✅ Dry run - transaction prepared\n");
+                report.order(order_hash.into(), OrderOutcome::Prepared, None);
                 continue;
             }
 
             let discriminator = get_discriminator("global", "submit_order");
             let mut instruction_data = Vec::new();
             instruction_data.extend_from_slice(&discriminator);
-            instruction_data.extend_from_slice(&borsh::to_vec(&order)?);
+            instruction_data.extend_from_slice(
+                &borsh::to_vec(&Order::from(&order)).map_err(SettlementError::encoding)?,
+            );
             instruction_data.extend_from_slice(&order_hash);
 
             let accounts = vec![
                 AccountMeta::new(state_pda, false),
                 AccountMeta::new(order_pda, false),
-                AccountMeta::new(self.keypair.pubkey(), true),
+                AccountMeta::new(self.keypair()?.pubkey(), true),
                 AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
             ];
 
@@ -290,30 +488,28 @@ impl ChainClient for SolanaClient {
                 data: instruction_data,
             };
 
-            let recent_blockhash = self.rpc_client.get_latest_blockhash()?;
-            let transaction = SolanaTransaction::new_signed_with_payer(
-                &[instruction],
-                Some(&self.keypair.pubkey()),
-                &[&self.keypair],
-                recent_blockhash,
-            );
-
-            match self.rpc_client.send_and_confirm_transaction(&transaction) {
-                Ok(signature) => {
-                    println!("   Tx signature: {signature}");
-                    println!("   ✅ Success\n");
+            match self.send(&[instruction], report).await {
+                Ok(sent) => {
+                    report.info("   ✅ Success\n");
+                    report.order(
+                        order_hash.into(),
+                        OrderOutcome::Submitted,
+                        Some(&sent.tx_hash),
+                    );
+                    report.transaction(sent);
                 }
                 Err(e) => {
-                    println!("   ❌ Failed: {e}\n");
-                    return Err(e.into());
+                    report.info(format!("   ❌ Failed: {e}\n"));
+                    report.order(order_hash.into(), OrderOutcome::Failed, None);
+                    return Err(e);
                 }
             }
         }
 
         if dry_run {
-            println!("🔍 Dry run completed - no transactions sent");
+            report.info("🔍 Dry run completed - no transactions sent");
         } else {
-            println!("✅ All orders submitted successfully!");
+            report.info("✅ All orders submitted successfully!");
         }
 
         Ok(())
@@ -321,86 +517,49 @@ impl ChainClient for SolanaClient {
 
     async fn settle_orders(
         &self,
-        proof_data: &ProofData,
+        proof_data: &SettlementArtifact,
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("⚡ Settling orders on {}...\n", self.config.chain.name());
-
-        let mut orders_to_settle: Vec<OrderProofJson> = Vec::new();
-
-        for order_proofs in proof_data.proofs_by_source_chain.values() {
-            for order_proof in order_proofs {
-                if order_proof.order.source_chain_id == self.config.chain.chain_id() {
-                    orders_to_settle.push(order_proof.clone());
-                }
-            }
-        }
-
-        if orders_to_settle.is_empty() {
-            println!("ℹ️  No orders found for {}", self.config.chain.name());
+        report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        let Some(ChainSettlement {
+            orders: orders_to_settle,
+            accumulator,
+        }) = ChainSettlement::prepare(proof_data, &self.config, report)?
+        else {
             return Ok(());
-        }
-
-        println!("📦 Found {} orders to settle", orders_to_settle.len());
-        for (i, order) in orders_to_settle.iter().enumerate() {
-            println!(
-                "   {}. {} → {} (amount: {} wei)",
-                i + 1,
-                order.order.source_chain_id,
-                order.order.receiver,
-                order.order.amount
-            );
-        }
+        };
+        let accumulator = AccumulatorProof {
+            transition: AccumulatorTransition::from(&accumulator.transition),
+            proof: accumulator.proof.iter().map(|p| p.0).collect(),
+        };
 
         let order_proofs: Vec<OrderProof> = orders_to_settle
             .iter()
-            .map(|op| {
-                let order_hash_bytes = hex::decode(op.order_hash.trim_start_matches("0x")).unwrap();
-                let mut order_hash = [0u8; 32];
-                order_hash.copy_from_slice(&order_hash_bytes);
-
-                let proof: Vec<[u8; 32]> = op
-                    .proof
-                    .iter()
-                    .map(|p| {
-                        let bytes = hex::decode(p.trim_start_matches("0x")).unwrap();
-                        let mut arr = [0u8; 32];
-                        arr.copy_from_slice(&bytes);
-                        arr
-                    })
-                    .collect();
-
-                let receiver_bytes = op.order.receiver.as_slice();
-                let mut receiver = [0u8; 20];
-                receiver.copy_from_slice(&receiver_bytes[..20]);
-
-                let amount_bytes = op.order.amount.to_be_bytes();
-
-                OrderProof {
-                    order: Order {
-                        source_chain_id: op.order.source_chain_id,
-                        destination_chain_id: op.order.destination_chain_id,
-                        receiver,
-                        amount: amount_bytes,
-                        block_number: op.order.block_number,
-                    },
-                    order_hash,
-                    proof,
-                }
+            .map(|op| OrderProof {
+                order: Order::from(&op.order),
+                order_hash: op.order_hash.0,
+                proof: op.proof.iter().map(|p| p.0).collect(),
+                nullifier: op.nullifier.0,
+                nullifier_proof: op.nullifier_proof.iter().map(|p| p.0).collect(),
             })
             .collect();
 
-        let sp1_public_inputs = hex::decode(proof_data.public_values.trim_start_matches("0x"))?;
-        let groth16_proof = hex::decode(proof_data.proof.trim_start_matches("0x"))?;
+        let sp1_public_inputs = proof_data.public_values.to_vec();
+        let groth16_proof = proof_data.proof.to_vec();
 
-        println!("📋 Transaction Details:");
-        println!("   Program ID: {}", self.program_id);
-        println!("   Public values: {} bytes", sp1_public_inputs.len());
-        println!("   Proof: {} bytes", groth16_proof.len());
-        println!();
+        report.info("📋 Transaction Details:");
+        report.info(format!("   Program ID: {}", self.program_id));
+        report.info(format!(
+            "   Public values: {} bytes",
+            sp1_public_inputs.len()
+        ));
+        report.info(format!("   Proof: {} bytes", groth16_proof.len()));
+        report.info("");
 
         if dry_run {
-            println!("✅ Dry run mode - transaction prepared successfully but not sent");
+            report.info("✅ Dry run mode - transaction prepared successfully but not sent");
+            let order_hashes = orders_to_settle.iter().map(|op| op.order_hash);
+            report.orders(order_hashes, OrderOutcome::Prepared, None);
             return Ok(());
         }
 
@@ -408,121 +567,143 @@ impl ChainClient for SolanaClient {
         let total_orders = order_proofs.len();
 
         if total_orders > 1 {
-            println!("⚠️  Settling orders individually to stay within transaction size limits\n");
+            report
+                .info("⚠️  Settling orders individually to stay within transaction size limits\n");
         }
 
         for (i, order_proof) in order_proofs.iter().enumerate() {
-            println!(
+            report.info(format!(
                 "📦 [{}/{}] Settling order 0x{}...",
                 i + 1,
                 total_orders,
                 hex::encode(&order_proof.order_hash[..4])
-            );
-
-            let discriminator = get_discriminator("global", "settle_orders");
-            let mut instruction_data = Vec::new();
-            instruction_data.extend_from_slice(&discriminator);
-            instruction_data.extend_from_slice(&borsh::to_vec(&sp1_public_inputs)?);
-            instruction_data.extend_from_slice(&borsh::to_vec(&groth16_proof)?);
-            instruction_data.extend_from_slice(&borsh::to_vec(&vec![order_proof.clone()])?);
+            ));
 
-            let (state_pda, _) = self.get_state_pda();
-            let (order_pda, _) = self.get_order_pda(&order_proof.order_hash);
-
-            let accounts = vec![
-                AccountMeta::new(state_pda, false),
-                AccountMeta::new(order_pda, false),
-            ];
-
-            let settle_instruction = Instruction {
-                program_id: self.program_id,
-                accounts,
-                data: instruction_data,
-            };
+            let settle_instruction = self.settle_instruction(
+                &sp1_public_inputs,
+                &groth16_proof,
+                &accumulator,
+                std::slice::from_ref(order_proof),
+            )?;
 
             let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
 
-            let recent_blockhash = self.rpc_client.get_latest_blockhash()?;
-            let transaction = SolanaTransaction::new_signed_with_payer(
-                &[compute_budget_ix, settle_instruction],
-                Some(&self.keypair.pubkey()),
-                &[&self.keypair],
-                recent_blockhash,
-            );
-
-            match self.rpc_client.send_and_confirm_transaction(&transaction) {
-                Ok(signature) => {
-                    println!("   Tx: {signature}");
-                    println!("   ✅ Success\n");
+            let order_hash = order_proof.order_hash.into();
+            match self
+                .send(&[compute_budget_ix, settle_instruction], report)
+                .await
+            {
+                Ok(sent) => {
+                    report.info("   ✅ Success\n");
+                    report.order(order_hash, OrderOutcome::Settled, Some(&sent.tx_hash));
+                    report.transaction(sent);
                 }
                 Err(e) => {
-                    println!("   ❌ Failed: {e}\n");
-                    return Err(e.into());
+                    report.info(format!("   ❌ Failed: {e}\n"));
+                    report.order(order_hash, OrderOutcome::Failed, None);
+                    return Err(e);
                 }
             }
         }
 
-        println!(
+        report.info(format!(
             "✅ All {} order{} settled successfully!",
             total_orders,
             if total_orders == 1 { "" } else { "s" }
-        );
+        ));
 
         Ok(())
     }
 
+    async fn program_vkey(&self) -> Result<FixedBytes<32>, SettlementError> {
+        let (state_pda, _) = self.get_state_pda();
+        let data = self
+            .rpc_client
+            .get_account_data(&state_pda)
+            .await
+            .map_err(rpc_error)?;
+
+        // Skip the Anchor discriminator of SettlementState
+        let vkey_hash = data.get(8..40).ok_or_else(|| {
+            SettlementError::config(
+                "Settlement state account is too short; is the program initialized?",
+            )
+        })?;
+        Ok(FixedBytes::from_slice(vkey_hash))
+    }
+
+    async fn order_status(
+        &self,
+        order_hash: FixedBytes<32>,
+    ) -> Result<OrderStatus, SettlementError> {
+        let (order_pda, _) = self.get_order_pda(&order_hash.0);
+        let Some(account) = self
+            .rpc_client
+            .get_account_with_commitment(&order_pda, self.rpc_client.commitment())
+            .await
+            .map_err(rpc_error)?
+            .value
+        else {
+            return Ok(OrderStatus::Unknown);
+        };
+
+        // OrderStatus is the Anchor discriminator, order_hash, then the settled flag
+        match account.data.get(40) {
+            Some(0) => Ok(OrderStatus::Submitted),
+            Some(_) => Ok(OrderStatus::Settled),
+            None => Err(SettlementError::Encoding(format!(
+                "Order account {order_pda} is too short"
+            ))),
+        }
+    }
+
     async fn reset_orders(
         &self,
-        proof_data: &ProofData,
+        proof_data: &SettlementArtifact,
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("🔄 Resetting orders on {}...\n", self.config.chain.name());
-
-        let chain_id_str = self.config.chain.chain_id().to_string();
+        report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        report.info(format!(
+            "🔄 Resetting orders on {}...\n",
+            self.config.display_name
+        ));
 
         let order_hashes: Vec<[u8; 32]> = proof_data
-            .proofs_by_source_chain
-            .get(&chain_id_str)
-            .map(|orders| {
-                orders
-                    .iter()
-                    .map(|op| {
-                        let bytes = hex::decode(op.order_hash.trim_start_matches("0x")).unwrap();
-                        let mut arr = [0u8; 32];
-                        arr.copy_from_slice(&bytes);
-                        arr
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
+            .orders_for(self.config.chain_id)
+            .iter()
+            .map(|op| op.order_hash.0)
+            .collect();
 
         if order_hashes.is_empty() {
-            println!("⏭️  No orders to reset");
+            report.info("⏭️  No orders to reset");
             return Ok(());
         }
 
-        println!(
+        report.info(format!(
             "📍 {} ({} orders)",
-            self.config.chain.name(),
+            self.config.display_name,
             order_hashes.len()
-        );
-        println!("   Program ID: {}", self.program_id);
+        ));
+        report.info(format!("   Program ID: {}", self.program_id));
 
+        let reported_hashes = order_hashes.iter().map(|hash| FixedBytes::from(*hash));
         if dry_run {
-            println!("   ✅ Dry run - transaction prepared");
+            report.info("   ✅ Dry run - transaction prepared");
+            report.orders(reported_hashes, OrderOutcome::Prepared, None);
             return Ok(());
         }
 
         let discriminator = get_discriminator("global", "reset_orders");
         let mut instruction_data = Vec::new();
         instruction_data.extend_from_slice(&discriminator);
-        instruction_data.extend_from_slice(&borsh::to_vec(&order_hashes)?);
+        instruction_data
+            .extend_from_slice(&borsh::to_vec(&order_hashes).map_err(SettlementError::encoding)?);
 
         let (state_pda, _) = self.get_state_pda();
 
         let mut accounts = vec![
             AccountMeta::new(state_pda, false),
-            AccountMeta::new(self.keypair.pubkey(), true),
+            AccountMeta::new(self.keypair()?.pubkey(), true),
         ];
 
         for order_hash in &order_hashes {
@@ -536,27 +717,60 @@ impl ChainClient for SolanaClient {
             data: instruction_data,
         };
 
-        println!("   📤 Sending transaction...");
+        report.info("   📤 Sending transaction...");
 
-        let recent_blockhash = self.rpc_client.get_latest_blockhash()?;
-        let transaction = SolanaTransaction::new_signed_with_payer(
-            &[instruction],
-            Some(&self.keypair.pubkey()),
-            &[&self.keypair],
-            recent_blockhash,
-        );
-
-        match self.rpc_client.send_and_confirm_transaction(&transaction) {
-            Ok(signature) => {
-                println!("   Tx: {signature}");
-                println!("   ✅ Success");
+        match self.send(&[instruction], report).await {
+            Ok(sent) => {
+                report.info("   ✅ Success");
+                report.orders(reported_hashes, OrderOutcome::Reset, Some(&sent.tx_hash));
+                report.transaction(sent);
             }
             Err(e) => {
-                println!("   ❌ Failed: {e}");
-                return Err(e.into());
+                report.info(format!("   ❌ Failed: {e}"));
+                report.orders(reported_hashes, OrderOutcome::Failed, None);
+                return Err(e);
             }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchor_codes_decode_to_their_variant() {
+        let error = |code| {
+            rpc_error(ClientError::from(TransactionError::InstructionError(
+                1,
+                InstructionError::Custom(code),
+            )))
+        };
+
+        assert_eq!(
+            error(6000),
+            SettlementError::ProofRejected("Invalid Groth16 proof (Anchor error 6000)".into())
+        );
+        assert_eq!(
+            error(6005),
+            SettlementError::AlreadySettled(
+                "Fill already used by another order (Anchor error 6005)".into()
+            )
+        );
+        assert_eq!(
+            error(6011),
+            SettlementError::Reverted(
+                "State account is already migrated (Anchor error 6011)".into()
+            )
+        );
+        assert_eq!(
+            error(6000 + SOLANA_PROGRAM_ERRORS.len() as u32),
+            SettlementError::Reverted(format!(
+                "custom program error {:#x}",
+                6000 + SOLANA_PROGRAM_ERRORS.len()
+            ))
+        );
+    }
+}
@@ -1,45 +1,114 @@
-use super::chain_client::{ChainClient, ChainConfig, OrderProofJson, ProofData, Transaction};
+use super::chain_client::{ChainClient, ChainSettlement, OrderStatus, Transaction};
+use super::error::{RevertKind, SettlementError};
+use super::registry::ChainConfig;
+use super::report::{OrderOutcome, Report, TxReport};
+use alloy_primitives::FixedBytes;
 use async_trait::async_trait;
 use garaga_rs::calldata::full_proof_with_hints::groth16::{
     get_groth16_calldata_felt, get_sp1_vk, Groth16Proof,
 };
 use garaga_rs::definitions::CurveID;
+use settlement_lib::SettlementArtifact;
 use starknet::{
     accounts::{Account, ExecutionEncoding, SingleOwnerAccount},
     core::{
-        types::{BlockId, BlockTag, Call, Felt},
+        types::{BlockId, BlockTag, Call, Felt, FunctionCall},
         utils::get_selector_from_name,
     },
     providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, Url},
     signers::{LocalWallet, SigningKey},
 };
+use std::fmt;
 use std::str::FromStr;
 use std::time::Duration;
 
+/// Panic strings of the Cairo settlement contract; the tests check them against the contract
+/// source. Panics not listed here are reported as plain reverts.
+const CONTRACT_PANICS: [(&str, RevertKind); 21] = [
+    ("Unsupported Bankai network", RevertKind::Reverted),
+    ("Invalid fill window", RevertKind::Reverted),
+    ("Order already exists", RevertKind::AlreadySettled),
+    ("Fill already used", RevertKind::AlreadySettled),
+    ("Proof verification failed", RevertKind::ProofRejected),
+    ("Wrong program", RevertKind::ProofRejected),
+    ("Wrong settlement chain", RevertKind::ProofRejected),
+    ("Invalid accumulator proof", RevertKind::ProofRejected),
+    ("Accumulator root mismatch", RevertKind::ProofRejected),
+    ("Invalid merkle proof", RevertKind::ProofRejected),
+    ("Invalid nullifier proof", RevertKind::ProofRejected),
+    ("Invalid public inputs length", RevertKind::ProofRejected),
+    ("Invalid output version", RevertKind::ProofRejected),
+    ("Unsupported output version", RevertKind::ProofRejected),
+    ("Invalid tree mode", RevertKind::ProofRejected),
+    ("Unsupported tree mode", RevertKind::ProofRejected),
+    ("Invalid Bankai network", RevertKind::ProofRejected),
+    ("Wrong Bankai network", RevertKind::ProofRejected),
+    ("Invalid block number", RevertKind::ProofRejected),
+    ("Invalid destination chain", RevertKind::ProofRejected),
+    ("Invalid order count", RevertKind::ProofRejected),
+];
+
+/// Classifies a failed Starknet request. A revert trace renders each Cairo panic string as
+/// `0x... ('reason')`; the first is the contract's own panic, the rest are the callers'
+/// `ENTRYPOINT_FAILED`.
+fn rpc_error(error: impl fmt::Debug + fmt::Display) -> SettlementError {
+    let trace = format!("{error:?}");
+    if let Some((_, rest)) = trace.split_once("('") {
+        if let Some((reason, _)) = rest.split_once("')") {
+            let kind = CONTRACT_PANICS
+                .iter()
+                .find(|(known, _)| *known == reason)
+                .map_or(RevertKind::Reverted, |(_, kind)| *kind);
+            return SettlementError::revert(kind, reason);
+        }
+    }
+    if trace.contains("InsufficientAccountBalance") || trace.contains("InsufficientResources") {
+        return SettlementError::InsufficientFunds(error.to_string());
+    }
+    SettlementError::rpc(error)
+}
+
+fn selector(name: &str) -> Result<Felt, SettlementError> {
+    get_selector_from_name(name).map_err(SettlementError::encoding)
+}
+
 pub struct StarknetClient {
     config: ChainConfig,
 }
 
 impl StarknetClient {
-    pub fn new(config: ChainConfig) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(config: ChainConfig) -> Result<Self, SettlementError> {
         Ok(Self { config })
     }
 
+    fn provider(&self) -> Result<JsonRpcClient<HttpTransport>, SettlementError> {
+        let url = Url::parse(&self.config.rpc_url).map_err(SettlementError::config)?;
+        Ok(JsonRpcClient::new(HttpTransport::new(url)))
+    }
+
+    fn contract_address(&self) -> Result<Felt, SettlementError> {
+        Felt::from_hex(&self.config.contract_address).map_err(SettlementError::config)
+    }
+
     async fn create_account(
         &self,
-    ) -> Result<
-        SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
-        Box<dyn std::error::Error>,
-    > {
-        let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(&self.config.rpc_url)?));
-
-        let private_key = std::env::var("STARKNET_PRIVATE_KEY")?;
-        let private_key_felt = Felt::from_hex(&private_key)?;
+    ) -> Result<SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>, SettlementError>
+    {
+        let provider = self.provider()?;
+
+        let private_key = self.config.signer_key().map_err(SettlementError::Config)?;
+        let private_key_felt = Felt::from_hex(&private_key).map_err(SettlementError::config)?;
         let signer = LocalWallet::from(SigningKey::from_secret_scalar(private_key_felt));
 
-        let address = Felt::from_hex(&std::env::var("STARKNET_ACCOUNT_ADDRESS")?)?;
+        let account_address = self.config.account.as_deref().ok_or_else(|| {
+            SettlementError::Config(format!(
+                "{} needs an account address in the chain registry",
+                self.config.display_name
+            ))
+        })?;
+        let address = Felt::from_hex(account_address).map_err(SettlementError::config)?;
 
-        let chain_id = provider.chain_id().await?;
+        let chain_id = provider.chain_id().await.map_err(rpc_error)?;
 
         let mut account =
             SingleOwnerAccount::new(provider, signer, address, chain_id, ExecutionEncoding::New);
@@ -50,161 +119,155 @@ impl StarknetClient {
         Ok(account)
     }
 
-    fn generate_proof_calldata(
-        &self,
-        _proof_data: &ProofData,
-    ) -> Result<Vec<Felt>, Box<dyn std::error::Error>> {
-        println!("🔧 Generating proof calldata on the fly with Garaga...");
-
-        // Load proof.json
-        let proof_json = std::fs::read_to_string("proof.json")?;
-        let proof_obj: serde_json::Value = serde_json::from_str(&proof_json)?;
-
-        let vkey = proof_obj["vkey"]
-            .as_str()
-            .ok_or("Missing vkey in proof.json")?;
-        let public_values = proof_obj["publicValues"]
-            .as_str()
-            .ok_or("Missing publicValues in proof.json")?;
-        let proof = proof_obj["proof"]
-            .as_str()
-            .ok_or("Missing proof in proof.json")?;
-
-        // Decode hex strings
-        let vkey_bytes = hex::decode(vkey.trim_start_matches("0x"))?;
-        let public_values_bytes = hex::decode(public_values.trim_start_matches("0x"))?;
-        let proof_bytes = hex::decode(proof.trim_start_matches("0x"))?;
-
-        // Generate Groth16 proof with Garaga
-        let sp1_groth16_vk = get_sp1_vk();
-        let groth16_proof = Groth16Proof::from_sp1(vkey_bytes, public_values_bytes, proof_bytes);
-        let calldata_bigint =
-            get_groth16_calldata_felt(&groth16_proof, &sp1_groth16_vk, CurveID::BN254)?;
-
-        // Convert to Starknet Felt
-        let calldata: Vec<Felt> = calldata_bigint
-            .iter()
-            .map(|bigint| {
-                let hex_str = format!("{:064x}", bigint.to_biguint());
-                Felt::from_hex(&format!("0x{hex_str}")).unwrap()
-            })
-            .collect();
-
-        println!("   ✅ Generated {} calldata elements", calldata.len());
-
-        Ok(calldata)
+    /// Prints a sent transaction's hash. The client doesn't wait for receipts, so only the hash
+    /// is known.
+    fn sent(&self, transaction_hash: Felt, report: &Report) -> TxReport {
+        let tx_hash = format!("{transaction_hash:#064x}");
+        let explorer_url = self.config.explorer_tx(&tx_hash);
+        report.info(format!("   Tx hash: {tx_hash}"));
+        if let Some(url) = &explorer_url {
+            report.info(format!("   Explorer: {url}"));
+        }
+        TxReport {
+            tx_hash,
+            explorer_url,
+            ..Default::default()
+        }
     }
 }
 
+/// Converts the artifact's SP1 Groth16 proof into the Garaga calldata the Starknet verifier
+/// takes, as 32-byte big-endian felts.
+pub fn groth16_calldata(
+    artifact: &SettlementArtifact,
+) -> Result<Vec<FixedBytes<32>>, Box<dyn std::error::Error>> {
+    let groth16_proof = Groth16Proof::from_sp1(
+        artifact.vkey.to_vec(),
+        artifact.public_values.to_vec(),
+        artifact.proof.to_vec(),
+    );
+    let calldata = get_groth16_calldata_felt(&groth16_proof, &get_sp1_vk(), CurveID::BN254)?;
+
+    calldata
+        .iter()
+        .map(|felt| {
+            let hex = format!("{:064x}", felt.to_biguint());
+            Ok(FixedBytes::<32>::from_str(&hex)?)
+        })
+        .collect()
+}
+
 #[async_trait]
 impl ChainClient for StarknetClient {
     async fn submit_orders(
         &self,
         transactions: &[Transaction],
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("📝 Submitting orders to {}...\n", self.config.chain.name());
+        report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        report.info(format!(
+            "📝 Submitting orders to {}...\n",
+            self.config.display_name
+        ));
 
         let filtered_txs: Vec<&Transaction> = transactions
             .iter()
-            .filter(|tx| tx.source_chain_id == self.config.chain.chain_id())
+            .filter(|tx| tx.source_chain_id == self.config.chain_id)
             .collect();
 
         if filtered_txs.is_empty() {
-            println!(
+            report.info(format!(
                 "ℹ️  No orders found with source {}",
-                self.config.chain.name()
-            );
+                self.config.display_name
+            ));
             return Ok(());
         }
 
-        println!("📦 Found {} orders to submit", filtered_txs.len());
+        report.info(format!("📦 Found {} orders to submit", filtered_txs.len()));
         for (i, tx) in filtered_txs.iter().enumerate() {
-            println!(
+            report.info(format!(
                 "   {}. From chain {} → {} (amount: {} wei, block: {})",
                 i + 1,
                 tx.source_chain_id,
                 tx.receiver,
                 tx.amount,
                 tx.block_number
-            );
+            ));
         }
-        println!();
+        report.info("");
 
-        println!("📋 Contract: {}", self.config.contract_address);
-        println!();
+        report.info(format!("📋 Contract: {}", self.config.contract_address));
+        report.info("");
 
         let account = self.create_account().await?;
-        let contract_address = Felt::from_hex(&self.config.contract_address)?;
+        let contract_address = self.contract_address()?;
 
         for (i, tx) in filtered_txs.iter().enumerate() {
-            println!("📤 [{}/{}] Submitting order...", i + 1, filtered_txs.len());
-
-            // Convert chain IDs and block number to u64
-            let source_chain_id = Felt::from(tx.source_chain_id);
-            let destination_chain_id = Felt::from(tx.destination_chain_id);
-            let block_number = Felt::from(tx.block_number);
-
-            // Convert receiver address to u256 (low, high)
-            // Ethereum address is 20 bytes, pad to 32 bytes for u256
-            let receiver_hex = tx.receiver.trim_start_matches("0x");
-            let mut receiver_bytes = [0u8; 32];
-            let addr_bytes = hex::decode(receiver_hex)?;
-            receiver_bytes[32 - addr_bytes.len()..].copy_from_slice(&addr_bytes);
-
-            let receiver_low = Felt::from_bytes_be_slice(&receiver_bytes[16..]);
-            let receiver_high = Felt::from_bytes_be_slice(&receiver_bytes[..16]);
+            report.info(format!(
+                "📤 [{}/{}] Submitting order...",
+                i + 1,
+                filtered_txs.len()
+            ));
 
-            // Convert amount to u256 (low, high)
-            let amount_u256 = u128::from_str(&tx.amount)?;
-            let amount_low = Felt::from(amount_u256);
-            let amount_high = Felt::ZERO;
+            let order = tx.order()?;
+            let receiver = order.receiver.into_word();
+            let token = order.token.into_word();
+            let amount: [u8; 32] = order.amount.to_be_bytes();
 
-            // Order struct serialization: [source_chain_id, destination_chain_id, receiver_low, receiver_high, amount_low, amount_high, block_number]
+            // Order struct serialization: [source_chain_id, destination_chain_id, receiver_low, receiver_high, token_low, token_high, amount_low, amount_high, block_number, fill_deadline]
             let calldata = vec![
-                source_chain_id,      // u64
-                destination_chain_id, // u64
-                receiver_low,         // u256.low
-                receiver_high,        // u256.high
-                amount_low,           // u256.low
-                amount_high,          // u256.high
-                block_number,         // u64
+                Felt::from(order.source_chain_id),          // u64
+                Felt::from(order.destination_chain_id),     // u64
+                Felt::from_bytes_be_slice(&receiver[16..]), // u256.low
+                Felt::from_bytes_be_slice(&receiver[..16]), // u256.high
+                Felt::from_bytes_be_slice(&token[16..]),    // u256.low
+                Felt::from_bytes_be_slice(&token[..16]),    // u256.high
+                Felt::from_bytes_be_slice(&amount[16..]),   // u256.low
+                Felt::from_bytes_be_slice(&amount[..16]),   // u256.high
+                Felt::from(order.block_number),             // u64
+                Felt::from(order.fill_deadline),            // u64
             ];
 
-            println!(
+            report.info(format!(
                 "   Order: {}-{} amount {} block {}",
                 tx.source_chain_id, tx.destination_chain_id, tx.amount, tx.block_number
-            );
-            println!("   Calldata: {calldata:?}");
+            ));
+            report.info(format!("   Calldata: {calldata:?}"));
 
+            let order_hash = order.hash();
             if dry_run {
-                println!("   ✅ Dry run - transaction prepared\n");
+                report.info("   ✅ Dry run - transaction prepared\n");
+                report.order(order_hash, OrderOutcome::Prepared, None);
                 continue;
             }
 
             let call = Call {
                 to: contract_address,
-                selector: get_selector_from_name("submit_order")?,
+                selector: selector("submit_order")?,
                 calldata,
             };
 
             match account.execute_v3(vec![call]).send().await {
                 Ok(result) => {
                     tokio::time::sleep(Duration::from_secs(5)).await;
-                    println!("   Tx hash: {:#064x}", result.transaction_hash);
-                    println!("   ✅ Success\n");
+                    let sent = self.sent(result.transaction_hash, report);
+                    report.info("   ✅ Success\n");
+                    report.order(order_hash, OrderOutcome::Submitted, Some(&sent.tx_hash));
+                    report.transaction(sent);
                 }
                 Err(e) => {
-                    println!("   ❌ Failed: {e}\n");
-                    return Err(e.into());
+                    let error = rpc_error(e);
+                    report.info(format!("   ❌ Failed: {error}\n"));
+                    report.order(order_hash, OrderOutcome::Failed, None);
+                    return Err(error);
                 }
             }
         }
 
         if dry_run {
-            println!("🔍 Dry run completed - no transactions sent");
+            report.info("🔍 Dry run completed - no transactions sent");
         } else {
-            println!("✅ All orders submitted successfully!");
+            report.info("✅ All orders submitted successfully!");
         }
 
         Ok(())
@@ -212,61 +275,75 @@ impl ChainClient for StarknetClient {
 
     async fn settle_orders(
         &self,
-        proof_data: &ProofData,
+        proof_data: &SettlementArtifact,
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("⚡ Settling orders on {}...\n", self.config.chain.name());
-
-        let mut orders_to_settle: Vec<OrderProofJson> = Vec::new();
-
-        for order_proofs in proof_data.proofs_by_source_chain.values() {
-            for order_proof in order_proofs {
-                if order_proof.order.source_chain_id == self.config.chain.chain_id() {
-                    orders_to_settle.push(order_proof.clone());
-                }
-            }
-        }
-
-        if orders_to_settle.is_empty() {
-            println!("ℹ️  No orders found for {}", self.config.chain.name());
+        report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        let Some(ChainSettlement {
+            orders: orders_to_settle,
+            accumulator,
+        }) = ChainSettlement::prepare(proof_data, &self.config, report)?
+        else {
             return Ok(());
-        }
-
-        println!("📦 Found {} orders to settle", orders_to_settle.len());
-        for (i, order) in orders_to_settle.iter().enumerate() {
-            println!(
-                "   {}. {} → {} (amount: {} wei)",
-                i + 1,
-                order.order.source_chain_id,
-                order.order.receiver,
-                order.order.amount
-            );
-        }
-        println!();
-
-        let proof_calldata = self.generate_proof_calldata(proof_data)?;
-        println!();
+        };
+        report.info("");
+
+        // A mock proof has no Groth16 points to convert, so only the order calldata is built
+        let proof_calldata = if proof_data.mock {
+            report.info("🧪 Mock proof - skipping Garaga proof calldata");
+            Vec::new()
+        } else if let Some(words) = proof_data.calldata.get(&self.config.chain_id) {
+            report.info(format!(
+                "🔧 Using {} precomputed Garaga calldata elements",
+                words.len()
+            ));
+            words.clone()
+        } else {
+            report.info("🔧 Generating proof calldata on the fly with Garaga...");
+            let words = groth16_calldata(proof_data).map_err(SettlementError::encoding)?;
+            report.info(format!("   ✅ Generated {} calldata elements", words.len()));
+            words
+        };
+        report.info("");
 
         let mut calldata = Vec::new();
 
         calldata.push(Felt::from(proof_calldata.len()));
-        calldata.extend(proof_calldata);
+        calldata.extend(
+            proof_calldata
+                .iter()
+                .map(|word| Felt::from_bytes_be(&word.0)),
+        );
+
+        // AccumulatorProof { transition: { chain_id, previous_root, root, count }, proof }
+        let transition = &accumulator.transition;
+        calldata.push(Felt::from(transition.chain_id));
+        for root in [transition.previous_root, transition.root] {
+            calldata.push(Felt::from_bytes_be_slice(&root[16..]));
+            calldata.push(Felt::from_bytes_be_slice(&root[..16]));
+        }
+        calldata.push(Felt::from(transition.count));
+        calldata.push(Felt::from(accumulator.proof.len()));
+        for proof_element in &accumulator.proof {
+            calldata.push(Felt::from_bytes_be_slice(&proof_element[16..]));
+            calldata.push(Felt::from_bytes_be_slice(&proof_element[..16]));
+        }
 
+        let order_hashes: Vec<FixedBytes<32>> =
+            orders_to_settle.iter().map(|op| op.order_hash).collect();
         calldata.push(Felt::from(orders_to_settle.len()));
 
-        for order_proof in &orders_to_settle {
-            let order_hash_bytes = hex::decode(order_proof.order_hash.trim_start_matches("0x"))?;
-            let order_hash_low = Felt::from_bytes_be_slice(&order_hash_bytes[16..]);
-            let order_hash_high = Felt::from_bytes_be_slice(&order_hash_bytes[..16]);
+        for order_proof in orders_to_settle {
+            let order_hash_low = Felt::from_bytes_be_slice(&order_proof.order_hash[16..]);
+            let order_hash_high = Felt::from_bytes_be_slice(&order_proof.order_hash[..16]);
 
             calldata.push(order_hash_low);
             calldata.push(order_hash_high);
 
             calldata.push(Felt::from(order_proof.proof.len()));
             for proof_element in &order_proof.proof {
-                let proof_bytes = hex::decode(proof_element.trim_start_matches("0x"))?;
-                let proof_low = Felt::from_bytes_be_slice(&proof_bytes[16..]);
-                let proof_high = Felt::from_bytes_be_slice(&proof_bytes[..16]);
+                let proof_low = Felt::from_bytes_be_slice(&proof_element[16..]);
+                let proof_high = Felt::from_bytes_be_slice(&proof_element[..16]);
                 calldata.push(proof_low);
                 calldata.push(proof_high);
             }
@@ -275,26 +352,36 @@ impl ChainClient for StarknetClient {
             let leaf_index_high = Felt::ZERO;
             calldata.push(leaf_index_low);
             calldata.push(leaf_index_high);
+
+            calldata.push(Felt::from_bytes_be_slice(&order_proof.nullifier[16..]));
+            calldata.push(Felt::from_bytes_be_slice(&order_proof.nullifier[..16]));
+
+            calldata.push(Felt::from(order_proof.nullifier_proof.len()));
+            for proof_element in &order_proof.nullifier_proof {
+                calldata.push(Felt::from_bytes_be_slice(&proof_element[16..]));
+                calldata.push(Felt::from_bytes_be_slice(&proof_element[..16]));
+            }
         }
 
-        println!("📋 Transaction Details:");
-        println!("   Contract: {}", self.config.contract_address);
-        println!("   Total calldata elements: {}", calldata.len());
-        println!();
+        report.info("📋 Transaction Details:");
+        report.info(format!("   Contract: {}", self.config.contract_address));
+        report.info(format!("   Total calldata elements: {}", calldata.len()));
+        report.info("");
 
         if dry_run {
-            println!("✅ Dry run mode - transaction prepared successfully but not sent");
+            report.info("✅ Dry run mode - transaction prepared successfully but not sent");
+            report.orders(order_hashes, OrderOutcome::Prepared, None);
             return Ok(());
         }
 
         let account = self.create_account().await?;
-        let contract_address = Felt::from_hex(&self.config.contract_address)?;
+        let contract_address = self.contract_address()?;
 
-        println!("📤 Sending transaction...");
+        report.info("📤 Sending transaction...");
 
         let call = Call {
             to: contract_address,
-            selector: get_selector_from_name("settle_orders")?,
+            selector: selector("settle_orders")?,
             calldata,
         };
 
@@ -306,74 +393,127 @@ impl ChainClient for StarknetClient {
             .await
         {
             Ok(result) => {
-                println!("   Tx hash: {:#064x}", result.transaction_hash);
-                println!("\n✅ Settlement successful!");
+                let sent = self.sent(result.transaction_hash, report);
+                report.info("\n✅ Settlement successful!");
+                report.orders(order_hashes, OrderOutcome::Settled, Some(&sent.tx_hash));
+                report.transaction(sent);
             }
             Err(e) => {
-                println!("\n❌ Transaction failed: {e}");
-                return Err(e.into());
+                let error = rpc_error(e);
+                report.info(format!("\n❌ Transaction failed: {error}"));
+                report.orders(order_hashes, OrderOutcome::Failed, None);
+                return Err(error);
             }
         }
 
         Ok(())
     }
 
+    async fn program_vkey(&self) -> Result<FixedBytes<32>, SettlementError> {
+        let result = self
+            .provider()?
+            .call(
+                FunctionCall {
+                    contract_address: self.contract_address()?,
+                    entry_point_selector: selector("get_vk")?,
+                    calldata: vec![],
+                },
+                BlockId::Tag(BlockTag::Latest),
+            )
+            .await
+            .map_err(rpc_error)?;
+
+        // A u256 comes back as (low, high) 128-bit limbs
+        let [low, high] = result[..] else {
+            return Err(SettlementError::Encoding(format!(
+                "get_vk returned {} felts, expected 2",
+                result.len()
+            )));
+        };
+        let mut vkey = [0u8; 32];
+        vkey[..16].copy_from_slice(&high.to_bytes_be()[16..]);
+        vkey[16..].copy_from_slice(&low.to_bytes_be()[16..]);
+        Ok(vkey.into())
+    }
+
+    async fn order_status(
+        &self,
+        order_hash: FixedBytes<32>,
+    ) -> Result<OrderStatus, SettlementError> {
+        let low = Felt::from_bytes_be_slice(&order_hash[16..]);
+        let high = Felt::from_bytes_be_slice(&order_hash[..16]);
+        let result = self
+            .provider()?
+            .call(
+                FunctionCall {
+                    contract_address: self.contract_address()?,
+                    entry_point_selector: selector("get_order_status")?,
+                    calldata: vec![low, high],
+                },
+                BlockId::Tag(BlockTag::Latest),
+            )
+            .await
+            .map_err(rpc_error)?;
+
+        // The contract only tracks settled orders, submitted ones read as false too
+        match result.first() {
+            Some(settled) if *settled != Felt::ZERO => Ok(OrderStatus::Settled),
+            Some(_) => Ok(OrderStatus::Unknown),
+            None => Err(SettlementError::encoding(
+                "get_order_status returned no value",
+            )),
+        }
+    }
+
     async fn reset_orders(
         &self,
-        proof_data: &ProofData,
+        proof_data: &SettlementArtifact,
         dry_run: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("🔄 Resetting orders on {}...\n", self.config.chain.name());
-
-        let chain_id_str = self.config.chain.chain_id().to_string();
-
-        let order_hashes: Vec<(Felt, Felt)> = proof_data
-            .proofs_by_source_chain
-            .get(&chain_id_str)
-            .map(|orders| {
-                orders
-                    .iter()
-                    .map(|op| {
-                        let bytes = hex::decode(op.order_hash.trim_start_matches("0x")).unwrap();
-                        let low = Felt::from_bytes_be_slice(&bytes[16..]);
-                        let high = Felt::from_bytes_be_slice(&bytes[..16]);
-                        (low, high)
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
+        report: &mut Report,
+    ) -> Result<(), SettlementError> {
+        report.info(format!(
+            "🔄 Resetting orders on {}...\n",
+            self.config.display_name
+        ));
+
+        let order_hashes: Vec<FixedBytes<32>> = proof_data
+            .orders_for(self.config.chain_id)
+            .iter()
+            .map(|op| op.order_hash)
+            .collect();
 
         if order_hashes.is_empty() {
-            println!("⏭️  No orders to reset");
+            report.info("⏭️  No orders to reset");
             return Ok(());
         }
 
-        println!(
+        report.info(format!(
             "📍 {} ({} orders)",
-            self.config.chain.name(),
+            self.config.display_name,
             order_hashes.len()
-        );
-        println!("   Contract: {}", self.config.contract_address);
+        ));
+        report.info(format!("   Contract: {}", self.config.contract_address));
 
         let mut calldata = vec![Felt::from(order_hashes.len())];
-        for (low, high) in order_hashes {
-            calldata.push(low);
-            calldata.push(high);
+        for order_hash in &order_hashes {
+            calldata.push(Felt::from_bytes_be_slice(&order_hash[16..]));
+            calldata.push(Felt::from_bytes_be_slice(&order_hash[..16]));
         }
 
         if dry_run {
-            println!("   ✅ Dry run - transaction prepared");
+            report.info("   ✅ Dry run - transaction prepared");
+            report.orders(order_hashes, OrderOutcome::Prepared, None);
             return Ok(());
         }
 
         let account = self.create_account().await?;
-        let contract_address = Felt::from_hex(&self.config.contract_address)?;
+        let contract_address = self.contract_address()?;
 
-        println!("   📤 Sending transaction...");
+        report.info("   📤 Sending transaction...");
 
         let call = Call {
             to: contract_address,
-            selector: get_selector_from_name("reset_orders")?,
+            selector: selector("reset_orders")?,
             calldata,
         };
 
@@ -385,15 +525,60 @@ impl ChainClient for StarknetClient {
             .await
         {
             Ok(result) => {
-                println!("   Tx: {:#064x}", result.transaction_hash);
-                println!("   ✅ Success");
+                let sent = self.sent(result.transaction_hash, report);
+                report.info("   ✅ Success");
+                report.orders(order_hashes, OrderOutcome::Reset, Some(&sent.tx_hash));
+                report.transaction(sent);
             }
             Err(e) => {
-                println!("   ❌ Failed: {e}");
-                return Err(e.into());
+                let error = rpc_error(e);
+                report.info(format!("   ❌ Failed: {error}"));
+                report.orders(order_hashes, OrderOutcome::Failed, None);
+                return Err(error);
             }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    const CONTRACT: &str = include_str!("../../../contracts/starknet/src/lib.cairo");
+
+    /// Every short string literal in a Cairo source outside comments. The contract only uses
+    /// them as panic reasons.
+    fn panic_strings(source: &str) -> BTreeSet<&str> {
+        source
+            .lines()
+            .filter_map(|line| line.split("//").next())
+            .flat_map(|code| code.split('\'').skip(1).step_by(2))
+            .collect()
+    }
+
+    #[test]
+    fn contract_panics_match_the_contract() {
+        let known: BTreeSet<&str> = CONTRACT_PANICS.iter().map(|(reason, _)| *reason).collect();
+        assert_eq!(
+            known.len(),
+            CONTRACT_PANICS.len(),
+            "duplicate panic strings"
+        );
+        assert_eq!(known, panic_strings(CONTRACT));
+    }
+
+    #[test]
+    fn revert_traces_decode_to_the_contract_panic() {
+        let error = rpc_error(
+            "Transaction execution error: 0x46696c6c20616c72656164792075736564 ('Fill already used'), \
+             0x454e545259504f494e545f4641494c4544 ('ENTRYPOINT_FAILED')",
+        );
+        assert_eq!(
+            error,
+            SettlementError::AlreadySettled("Fill already used".into())
+        );
+    }
+}
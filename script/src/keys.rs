@@ -0,0 +1,58 @@
+use alloy_primitives::{keccak256, FixedBytes};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{HashableKey, SP1ProvingKey, SP1VerifyingKey};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::SETTLEMENT_ELF;
+
+/// Identifies a settlement program build, written to `vkey.json` after setup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VkeyArtifact {
+    /// Keccak256 of the settlement ELF
+    pub elf_hash: FixedBytes<32>,
+    /// The program vkey hash the settlement contracts verify against
+    pub vkey: FixedBytes<32>,
+}
+
+impl VkeyArtifact {
+    pub fn new(vk: &SP1VerifyingKey) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            elf_hash: keccak256(SETTLEMENT_ELF),
+            vkey: FixedBytes::from_str(&vk.bytes32())?,
+        })
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Loads the proving and verifying keys cached under `keys_dir` for the current ELF, running
+/// `setup` and caching its result on a miss. Keys are keyed by the ELF hash, so a rebuilt
+/// program never reuses stale keys.
+pub fn load_or_setup(
+    keys_dir: &str,
+    setup: impl FnOnce(&[u8]) -> (SP1ProvingKey, SP1VerifyingKey),
+) -> Result<(SP1ProvingKey, SP1VerifyingKey), Box<dyn std::error::Error>> {
+    let dir = Path::new(keys_dir).join(keccak256(SETTLEMENT_ELF).to_string());
+    let pk_path = dir.join("pk.bin");
+    let vk_path = dir.join("vk.bin");
+
+    if pk_path.exists() && vk_path.exists() {
+        println!("🔑 Using cached keys from {}", dir.display());
+        let pk = bincode::deserialize(&fs::read(&pk_path)?)?;
+        let vk = bincode::deserialize(&fs::read(&vk_path)?)?;
+        return Ok((pk, vk));
+    }
+
+    println!("🔑 No cached keys for this ELF, running setup...");
+    let (pk, vk) = setup(SETTLEMENT_ELF);
+    fs::create_dir_all(&dir)?;
+    fs::write(&pk_path, bincode::serialize(&pk)?)?;
+    fs::write(&vk_path, bincode::serialize(&vk)?)?;
+    Ok((pk, vk))
+}
@@ -1 +1,5 @@
 pub mod client;
+pub mod keys;
+
+/// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
+pub const SETTLEMENT_ELF: &[u8] = sp1_sdk::include_elf!("settlement-program");